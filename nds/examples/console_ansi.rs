@@ -3,35 +3,56 @@
 
 use anyhow::Result;
 use core::fmt::Write;
-use nds::sys::console::Console;
+use nds::sys::console::{Color, Console, Intensity, Styled, Terminal};
 use nds::sys::input::Keys;
 
 #[nds::entry]
 fn main() -> Result<()> {
     let mut console = Console::demo();
 
-    writeln!(console, "\x1b[2J")?;
-    writeln!(console, "\x1b[8;10HHello World!")?;
-    writeln!(console, "\x1b[8ALine 0")?;
-    writeln!(console, "\x1b[28DColumn 0")?;
-    writeln!(console, "\x1b[19BLine 19")?;
-    writeln!(console, "\x1b[5CColumn 20")?;
-    writeln!(console, "\x1b[14;4H")?;
+    const COLORS: [Color; 8] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+
+    console.clear();
+    console.set_cursor(8, 10);
+    writeln!(console, "Hello World!")?;
+    console.move_cursor(-8, 0);
+    writeln!(console, "Line 0")?;
+    console.move_cursor(0, -28);
+    writeln!(console, "Column 0")?;
+    console.move_cursor(19, 0);
+    writeln!(console, "Line 19")?;
+    console.move_cursor(0, 5);
+    writeln!(console, "Column 20")?;
+    console.set_cursor(14, 4);
 
     for (c, i) in (b'A'..=b'Z').zip(30..) {
-        let color = 30 + (i % 8);
-        let intensity = i / 8;
-        write!(console, "\x1b[{};{}m{}", color, intensity, c as char)?;
+        let intensity = match (i / 8) % 2 {
+            0 => Intensity::Normal,
+            _ => Intensity::Bright,
+        };
+        let styled = Styled::new(c as char)
+            .foreground(COLORS[i % 8])
+            .intensity(intensity);
+        write!(console, "{styled}")?;
     }
 
-    writeln!(console, "\x1b[39;0m")?;
-    writeln!(console, "\x1b[23;0HPress START to exit to loader")?;
+    console.set_cursor(23, 0);
+    writeln!(console, "Press START to exit to loader")?;
 
     loop {
         nds::sys::interrupt::swi_wait_for_vblank();
         Keys::scan();
 
-        if Keys::down().contains(Keys::START) {
+        if Keys::down().intersects(Keys::START) {
             break;
         }
     }
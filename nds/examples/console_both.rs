@@ -19,7 +19,7 @@ fn main() -> Result<()> {
         nds::sys::interrupt::swi_wait_for_vblank();
         Keys::scan();
 
-        if Keys::down().contains(Keys::START) {
+        if Keys::down().intersects(Keys::START) {
             break;
         }
     }
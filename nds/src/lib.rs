@@ -11,4 +11,4 @@ pub mod std {
     pub use nds_std::*;
 }
 
-pub use sys::{dtcm, entry, itcm};
+pub use sys::{dtcm, entry, include_font, interrupt, itcm};
@@ -1,4 +1,5 @@
 #[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Type {
     Text8Bpp = nds_sys::BgType_BgType_Text8bpp,
     Text4Bpp = nds_sys::BgType_BgType_Text4bpp,
@@ -9,7 +10,11 @@ pub enum Type {
 }
 
 #[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Size {
     R128x128 = nds_sys::BgSize_BgSize_R_128x128,
     T256x256 = nds_sys::BgSize_BgSize_T_256x256,
+    T512x256 = nds_sys::BgSize_BgSize_T_512x256,
+    T256x512 = nds_sys::BgSize_BgSize_T_256x512,
+    T512x512 = nds_sys::BgSize_BgSize_T_512x512,
 }
@@ -1,32 +1,106 @@
 //! Traits, helpers, and type definitions for core I/O functionality.
 
+mod ansi;
+#[cfg(feature = "sys")]
+mod builder;
+mod code_page;
+#[cfg(feature = "sys")]
+mod debug;
+#[cfg(feature = "sys")]
+mod font;
+#[cfg(feature = "sys")]
+mod keyboard;
+mod virtual_console;
+
+pub use ansi::*;
+#[cfg(feature = "sys")]
+pub use builder::*;
+pub use code_page::*;
+#[cfg(feature = "sys")]
+pub use debug::*;
+#[cfg(feature = "sys")]
+pub use font::*;
+#[cfg(feature = "sys")]
+pub use keyboard::*;
+pub use virtual_console::*;
+
+#[cfg(feature = "alloc")]
 use crate::background::{Size, Type};
-use alloc::ffi::CString;
-use core::fmt::{Error, Result, Write};
-use core::mem::zeroed;
+#[cfg(feature = "alloc")]
+use crate::video::Engine;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "sys")]
+use core::fmt::{Result, Write};
+#[cfg(feature = "alloc")]
+use core::ptr::null_mut;
+#[cfg(feature = "sys")]
 use nds_sys::PrintConsole;
+#[cfg(feature = "alloc")]
+use portable_atomic::{AtomicPtr, Ordering};
+
+/// A dropped console that was still selected, kept alive until libnds stops pointing at it.
+#[cfg(feature = "alloc")]
+static RETIRED: AtomicPtr<PrintConsole> = AtomicPtr::new(null_mut());
 
 /// Console structure used to store the state of a console render context.
-#[repr(transparent)]
-pub struct Console(*mut PrintConsole);
+///
+/// Consoles created with [`Console::top`] and [`Console::bottom`] own their state on the heap,
+/// so any number of them can exist independently. Without the `alloc` feature,
+/// [`ConsoleBuilder::build_in`] keeps the state in a [`ConsoleStorage`] instead.
+///
+/// Dropping the selected console keeps its state alive until another console is selected,
+/// so output printed through libnds never reaches freed state.
+#[cfg(feature = "sys")]
+pub struct Console {
+    raw: *mut PrintConsole,
+    #[cfg(feature = "alloc")]
+    owned: bool,
+    code_page: CodePage,
+}
+
+// SAFETY: The console state is only accessed through the console itself or while it is selected.
+#[cfg(feature = "sys")]
+unsafe impl Send for Console {}
 
+#[cfg(feature = "sys")]
 impl Console {
     /// Initialize a new console on the top screen.
+    ///
+    /// Each background layer uses its own map, so consoles on different layers of the same
+    /// screen do not overlap. Use [`ConsoleBuilder`] for full control over the console.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn top(layer: i32, kind: Type, size: Size) -> Self {
-        let kind = kind as u32;
-        let size = size as u32;
-
-        Self(unsafe { nds_sys::consoleInit(zeroed(), layer, kind, size, 31, 0, true, true) })
+        Self::builder()
+            .engine(Engine::Main)
+            .layer(layer)
+            .kind(kind)
+            .size(size)
+            .map_base(31 - layer)
+            .build()
     }
 
     /// Initialize a new console on the bottom screen.
+    ///
+    /// Each background layer uses its own map, so consoles on different layers of the same
+    /// screen do not overlap. Use [`ConsoleBuilder`] for full control over the console.
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn bottom(layer: i32, kind: Type, size: Size) -> Self {
-        let kind = kind as u32;
-        let size = size as u32;
+        Self::builder()
+            .engine(Engine::Sub)
+            .layer(layer)
+            .kind(kind)
+            .size(size)
+            .map_base(31 - layer)
+            .build()
+    }
 
-        Self(unsafe { nds_sys::consoleInit(zeroed(), layer, kind, size, 31, 0, false, true) })
+    /// Creates a builder to configure a new console.
+    #[inline]
+    pub const fn builder() -> ConsoleBuilder {
+        ConsoleBuilder::new()
     }
 
     /// Initialize the console to a default state for prototyping.
@@ -36,19 +110,54 @@ impl Console {
     /// Print functionality can be utilized with just this call.
     #[inline(always)]
     pub fn demo() -> Self {
-        Self(unsafe { nds_sys::consoleDemoInit() })
+        Self {
+            raw: unsafe { nds_sys::consoleDemoInit() },
+            #[cfg(feature = "alloc")]
+            owned: false,
+            code_page: CodePage::CP437,
+        }
     }
 
     /// Make the specified console the render target.
     #[inline]
     pub fn select(&self) {
-        unsafe { nds_sys::consoleSelect(self.0) };
+        unsafe { nds_sys::consoleSelect(self.raw) };
+    }
+
+    /// Restricts the console to a window, in characters, and moves the cursor into it.
+    #[inline]
+    pub fn set_window(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe { nds_sys::consoleSetWindow(self.raw, x, y, width, height) };
+    }
+
+    /// Loads a custom font into the console and uses it for all text printed afterwards.
+    ///
+    /// The font replaces the tiles at the tile base of the console,
+    /// so text printed before is redrawn with the new font.
+    /// The code page of the font replaces the code page of the console.
+    #[inline]
+    pub fn set_font(&mut self, font: &ConsoleFont) {
+        self.code_page = font.code_page;
+        let mut font = font.raw();
+        unsafe { nds_sys::consoleSetFont(self.raw, &mut font) };
+    }
+
+    /// Returns the code page text is translated into before printing.
+    #[inline]
+    pub fn code_page(&self) -> CodePage {
+        self.code_page
+    }
+
+    /// Sets the code page text is translated into before printing.
+    #[inline]
+    pub fn set_code_page(&mut self, code_page: CodePage) {
+        self.code_page = code_page;
     }
 
     /// Sets the color to use to print new text.
     #[inline]
     pub fn set_color(&mut self, color: Color) {
-        unsafe { nds_sys::consoleSetColor(self.0, color as u32) };
+        unsafe { nds_sys::consoleSetColor(self.raw, color as u32) };
     }
 
     /// Clears the console and returns the cursor to the top left corner.
@@ -59,34 +168,106 @@ impl Console {
     }
 }
 
+#[cfg(feature = "sys")]
 impl Write for Console {
+    /// Writes the string character by character, translated into the code page of the console.
     fn write_str(&mut self, s: &str) -> Result {
         self.select();
 
-        let c_str = CString::new(s).map_err(|_| Error)?;
-        unsafe { nds_sys::printf(c_str.as_ptr()) };
+        for character in s.chars() {
+            let code = self.code_page.encode(character);
+            // SAFETY: Writing to stdout only renders the character on the selected console.
+            unsafe { nds_sys::putchar(code as i32) };
+        }
 
         Ok(())
     }
 }
 
+#[cfg(feature = "alloc")]
+impl Drop for Console {
+    fn drop(&mut self) {
+        if !self.owned {
+            return;
+        }
+
+        // SAFETY: Selecting a console has no side effects besides changing the render target,
+        // which is restored right away.
+        let current = unsafe {
+            let current = nds_sys::consoleSelect(self.raw);
+            nds_sys::consoleSelect(current);
+            current
+        };
+
+        // A selected console is retired instead of freed, and the previously retired one is
+        // freed as soon as libnds no longer points at it.
+        let unused = match current == self.raw {
+            true => RETIRED.swap(self.raw, Ordering::Relaxed),
+            false => {
+                // SAFETY: Only consoles built on the heap are owned, and this one is not selected.
+                unsafe { drop(Box::from_raw(self.raw)) };
+                match RETIRED.load(Ordering::Relaxed) == current {
+                    true => null_mut(),
+                    false => RETIRED.swap(null_mut(), Ordering::Relaxed),
+                }
+            }
+        };
+
+        if !unused.is_null() {
+            // SAFETY: Retired consoles are owned consoles that are no longer selected.
+            unsafe { drop(Box::from_raw(unused)) };
+        }
+    }
+}
+
 /// Colors of the default palettes of libnds.
+///
+/// The discriminants are the palette indices used by libnds.
 #[repr(u32)]
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Color {
-    Black = nds_sys::ConsoleColor_CONSOLE_BLACK,
-    Red = nds_sys::ConsoleColor_CONSOLE_RED,
-    Green = nds_sys::ConsoleColor_CONSOLE_GREEN,
-    Yellow = nds_sys::ConsoleColor_CONSOLE_YELLOW,
-    Blue = nds_sys::ConsoleColor_CONSOLE_BLUE,
-    Magenta = nds_sys::ConsoleColor_CONSOLE_MAGENTA,
-    Cyan = nds_sys::ConsoleColor_CONSOLE_CYAN,
-    RedLight = nds_sys::ConsoleColor_CONSOLE_LIGHT_RED,
-    GreenLight = nds_sys::ConsoleColor_CONSOLE_LIGHT_GREEN,
-    YellowLight = nds_sys::ConsoleColor_CONSOLE_LIGHT_YELLOW,
-    BlueLight = nds_sys::ConsoleColor_CONSOLE_LIGHT_BLUE,
-    MagentaLight = nds_sys::ConsoleColor_CONSOLE_LIGHT_MAGENTA,
-    CyanLight = nds_sys::ConsoleColor_CONSOLE_LIGHT_CYAN,
+    Black = 0,
+    Red = 1,
+    Green = 2,
+    Yellow = 3,
+    Blue = 4,
+    Magenta = 5,
+    Cyan = 6,
+    RedLight = 9,
+    GreenLight = 10,
+    YellowLight = 11,
+    BlueLight = 12,
+    MagentaLight = 13,
+    CyanLight = 14,
     #[default]
-    White = nds_sys::ConsoleColor_CONSOLE_WHITE,
+    White = 15,
 }
+
+#[cfg(feature = "sys")]
+const _: () = {
+    use nds_sys::*;
+
+    let colors = [
+        (Color::Black, ConsoleColor_CONSOLE_BLACK),
+        (Color::Red, ConsoleColor_CONSOLE_RED),
+        (Color::Green, ConsoleColor_CONSOLE_GREEN),
+        (Color::Yellow, ConsoleColor_CONSOLE_YELLOW),
+        (Color::Blue, ConsoleColor_CONSOLE_BLUE),
+        (Color::Magenta, ConsoleColor_CONSOLE_MAGENTA),
+        (Color::Cyan, ConsoleColor_CONSOLE_CYAN),
+        (Color::RedLight, ConsoleColor_CONSOLE_LIGHT_RED),
+        (Color::GreenLight, ConsoleColor_CONSOLE_LIGHT_GREEN),
+        (Color::YellowLight, ConsoleColor_CONSOLE_LIGHT_YELLOW),
+        (Color::BlueLight, ConsoleColor_CONSOLE_LIGHT_BLUE),
+        (Color::MagentaLight, ConsoleColor_CONSOLE_LIGHT_MAGENTA),
+        (Color::CyanLight, ConsoleColor_CONSOLE_LIGHT_CYAN),
+        (Color::White, ConsoleColor_CONSOLE_WHITE),
+    ];
+
+    let mut index = 0;
+    while index < colors.len() {
+        let (color, raw) = colors[index];
+        assert!(color as u32 == raw, "Colors must match the libnds palette");
+        index += 1;
+    }
+};
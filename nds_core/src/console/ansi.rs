@@ -0,0 +1,168 @@
+use super::Color;
+#[cfg(feature = "sys")]
+use super::Console;
+use core::fmt::{Display, Formatter, Result, Write};
+
+/// Text output understanding the ANSI escape sequences of the libnds console.
+///
+/// This is implemented by [`Console`] and [`VirtualConsole`](super::VirtualConsole),
+/// so text UIs written against it can be tested on the host.
+pub trait Terminal: Write {
+    /// Moves the cursor to the given zero-based row and column.
+    #[inline]
+    fn set_cursor(&mut self, row: u8, column: u8) {
+        write!(self, "\x1b[{row};{column}H").ok();
+    }
+
+    /// Moves the cursor relative to its current position.
+    ///
+    /// Positive values move down and right, negative values move up and left.
+    fn move_cursor(&mut self, rows: i8, columns: i8) {
+        match rows {
+            ..0 => write!(self, "\x1b[{}A", rows.unsigned_abs()),
+            1.. => write!(self, "\x1b[{rows}B"),
+            0 => Ok(()),
+        }
+        .ok();
+
+        match columns {
+            ..0 => write!(self, "\x1b[{}D", columns.unsigned_abs()),
+            1.. => write!(self, "\x1b[{columns}C"),
+            0 => Ok(()),
+        }
+        .ok();
+    }
+
+    /// Saves the current cursor position.
+    #[inline]
+    fn save_cursor(&mut self) {
+        self.write_str("\x1b[s").ok();
+    }
+
+    /// Restores the cursor position saved by [`Terminal::save_cursor`].
+    #[inline]
+    fn restore_cursor(&mut self) {
+        self.write_str("\x1b[u").ok();
+    }
+
+    /// Clears the line the cursor is on.
+    #[inline]
+    fn clear_line(&mut self) {
+        self.write_str("\x1b[2K").ok();
+    }
+
+    /// Clears the screen and returns the cursor to the top left corner.
+    #[inline]
+    fn clear_screen(&mut self) {
+        self.write_str("\x1b[2J").ok();
+    }
+}
+
+#[cfg(feature = "sys")]
+impl Terminal for Console {}
+
+/// Intensity of styled text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Intensity {
+    #[default]
+    Normal,
+    Bright,
+}
+
+/// A value displayed with a foreground color, background color and intensity.
+///
+/// The style is reset to the default afterwards.
+#[derive(Debug, Clone, Copy)]
+pub struct Styled<T> {
+    value: T,
+    foreground: Option<Color>,
+    background: Option<Color>,
+    intensity: Intensity,
+}
+
+impl<T: Display> Styled<T> {
+    /// Wraps a value without any styling.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            foreground: None,
+            background: None,
+            intensity: Intensity::Normal,
+        }
+    }
+
+    /// Sets the foreground color.
+    ///
+    /// The light colors imply [`Intensity::Bright`].
+    #[inline]
+    pub const fn foreground(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    /// Sets the background color.
+    #[inline]
+    pub const fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Sets the intensity.
+    #[inline]
+    pub const fn intensity(mut self, intensity: Intensity) -> Self {
+        self.intensity = intensity;
+        self
+    }
+}
+
+impl<T: Display> Display for Styled<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut bright = self.intensity == Intensity::Bright;
+
+        f.write_str("\x1b[0")?;
+        if let Some(color) = self.foreground {
+            let (code, light) = color.ansi();
+            bright |= light;
+            write!(f, ";{}", 30 + code)?;
+        }
+        if let Some(color) = self.background {
+            write!(f, ";{}", 40 + color.ansi().0)?;
+        }
+        if bright {
+            f.write_str(";1")?;
+        }
+
+        write!(f, "m{}\x1b[39;0m", self.value)
+    }
+}
+
+impl Color {
+    /// Returns the ANSI color code and whether it is a light color.
+    #[inline]
+    pub(crate) const fn ansi(self) -> (u8, bool) {
+        let color = self as u8;
+        (color & 7, color >= 8)
+    }
+
+    /// Returns the color for an ANSI color code and intensity.
+    #[inline]
+    pub(crate) const fn from_ansi(code: u8, bright: bool) -> Self {
+        match (code, bright) {
+            (0, _) => Self::Black,
+            (1, false) => Self::Red,
+            (2, false) => Self::Green,
+            (3, false) => Self::Yellow,
+            (4, false) => Self::Blue,
+            (5, false) => Self::Magenta,
+            (6, false) => Self::Cyan,
+            (1, true) => Self::RedLight,
+            (2, true) => Self::GreenLight,
+            (3, true) => Self::YellowLight,
+            (4, true) => Self::BlueLight,
+            (5, true) => Self::MagentaLight,
+            (6, true) => Self::CyanLight,
+            _ => Self::White,
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use super::{CodePage, Console, ConsoleFont};
+use crate::background::{Size, Type};
+use crate::video::Engine;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::mem::zeroed;
+use nds_sys::PrintConsole;
+
+/// Storage for the state of a console, for consoles that are not allocated on the heap.
+///
+/// ```ignore
+/// static mut STORAGE: ConsoleStorage = ConsoleStorage::new();
+///
+/// let console = Console::builder().build_in(unsafe { &mut *&raw mut STORAGE });
+/// ```
+#[repr(transparent)]
+pub struct ConsoleStorage(PrintConsole);
+
+impl ConsoleStorage {
+    /// Creates storage for an uninitialized console.
+    #[inline]
+    pub const fn new() -> Self {
+        // SAFETY: The console state is plain data and is initialized by the builder before use.
+        Self(unsafe { zeroed() })
+    }
+}
+
+impl Default for ConsoleStorage {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A builder for consoles with full control over their background and graphics.
+///
+/// The defaults match [`Console::demo`]: background layer 0 of the sub engine,
+/// a 4bpp 256x256 text background, map base 31, tile base 0 and the default font.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsoleBuilder {
+    engine: Engine,
+    layer: i32,
+    kind: Type,
+    size: Size,
+    map_base: i32,
+    tile_base: i32,
+    font: Option<ConsoleFont>,
+    code_page: Option<CodePage>,
+    palette: Option<u8>,
+    load_graphics: bool,
+    window: Option<(i32, i32, i32, i32)>,
+}
+
+impl ConsoleBuilder {
+    /// Creates a builder with the default console configuration.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            engine: Engine::Sub,
+            layer: 0,
+            kind: Type::Text4Bpp,
+            size: Size::T256x256,
+            map_base: 31,
+            tile_base: 0,
+            font: None,
+            code_page: None,
+            palette: None,
+            load_graphics: true,
+            window: None,
+        }
+    }
+
+    /// Sets the engine, and with it the screen, the console is drawn by.
+    #[inline]
+    pub const fn engine(mut self, engine: Engine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Sets the background layer used by the console.
+    #[inline]
+    pub const fn layer(mut self, layer: i32) -> Self {
+        assert!(
+            layer >= 0 && layer < 4,
+            "Background layer must be between 0 and 3"
+        );
+        self.layer = layer;
+        self
+    }
+
+    /// Sets the type of the console background.
+    #[inline]
+    pub const fn kind(mut self, kind: Type) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets the size of the console background.
+    #[inline]
+    pub const fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets the 2KB block of background VRAM the tile map is stored at.
+    #[inline]
+    pub const fn map_base(mut self, map_base: i32) -> Self {
+        assert!(
+            map_base >= 0 && map_base < 32,
+            "Map base must be between 0 and 31"
+        );
+        self.map_base = map_base;
+        self
+    }
+
+    /// Sets the 16KB block of background VRAM the font tiles are stored at.
+    #[inline]
+    pub const fn tile_base(mut self, tile_base: i32) -> Self {
+        assert!(
+            tile_base >= 0 && tile_base < 16,
+            "Tile base must be between 0 and 15"
+        );
+        self.tile_base = tile_base;
+        self
+    }
+
+    /// Uses a custom font instead of the default libnds font.
+    #[inline]
+    pub const fn font(mut self, font: ConsoleFont) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the code page text is translated into, overriding the code page of the font.
+    #[inline]
+    pub const fn code_page(mut self, code_page: CodePage) -> Self {
+        self.code_page = Some(code_page);
+        self
+    }
+
+    /// Sets the palette bank text is printed with.
+    #[inline]
+    pub const fn palette(mut self, bank: u8) -> Self {
+        assert!(bank < 16, "Palette bank must be between 0 and 15");
+        self.palette = Some(bank);
+        self
+    }
+
+    /// Sets whether the default font graphics and palette are loaded into VRAM.
+    ///
+    /// Disable this when another console already loaded the font at the same tile base.
+    /// A custom font is always loaded.
+    #[inline]
+    pub const fn load_graphics(mut self, load_graphics: bool) -> Self {
+        self.load_graphics = load_graphics;
+        self
+    }
+
+    /// Restricts the console to a window, in characters, so it can share a screen with
+    /// other graphics on the same background.
+    #[inline]
+    pub const fn window(mut self, x: i32, y: i32, width: i32, height: i32) -> Self {
+        self.window = Some((x, y, width, height));
+        self
+    }
+
+    /// Initializes the console with its state allocated on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn build(self) -> Console {
+        let mut console = self.init(Box::into_raw(Box::new(PrintConsole::default())));
+        console.owned = true;
+        console
+    }
+
+    /// Initializes the console with its state kept in `storage`.
+    ///
+    /// The storage is never freed, so it stays valid for libnds even after the console is dropped.
+    #[inline]
+    pub fn build_in(self, storage: &'static mut ConsoleStorage) -> Console {
+        self.init(&raw mut storage.0)
+    }
+
+    fn init(self, raw: *mut PrintConsole) -> Console {
+        let main = matches!(self.engine, Engine::Main);
+        let load_graphics = self.load_graphics && self.font.is_none();
+
+        // SAFETY: `raw` points to a valid console that outlives its use by libnds.
+        unsafe {
+            nds_sys::consoleInit(
+                raw,
+                self.layer,
+                self.kind as u32,
+                self.size as u32,
+                self.map_base,
+                self.tile_base,
+                main,
+                load_graphics,
+            )
+        };
+
+        if let Some(font) = self.font {
+            let mut font = font.raw();
+            // SAFETY: The console is initialized and libnds copies the font description.
+            unsafe { nds_sys::consoleSetFont(raw, &mut font) };
+        }
+
+        if let Some(bank) = self.palette {
+            // SAFETY: Console colors select the palette bank text is printed with.
+            unsafe { nds_sys::consoleSetColor(raw, bank as u32) };
+        }
+
+        if let Some((x, y, width, height)) = self.window {
+            // SAFETY: The console is initialized.
+            unsafe { nds_sys::consoleSetWindow(raw, x, y, width, height) };
+        }
+
+        let code_page = match (self.code_page, self.font) {
+            (Some(code_page), _) => code_page,
+            (None, Some(font)) => font.code_page,
+            (None, None) => CodePage::CP437,
+        };
+
+        Console {
+            raw,
+            #[cfg(feature = "alloc")]
+            owned: false,
+            code_page,
+        }
+    }
+}
+
+impl Default for ConsoleBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,113 @@
+/// Characters of code page 437 from `0x80` to `0xFF`.
+const CP437: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{A0}', //
+];
+
+/// Translation of Unicode characters into the 8-bit character codes of a console font.
+///
+/// ASCII is always passed through unchanged, while `extended` lists the characters
+/// of codes `0x80` and up. Characters without a code are printed as `replacement`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodePage {
+    /// Characters of the codes starting at `0x80`.
+    pub extended: &'static [char],
+    /// Code printed for characters not in the code page.
+    pub replacement: u8,
+}
+
+impl CodePage {
+    /// Plain ASCII, replacing everything else with `?`.
+    pub const ASCII: Self = Self {
+        extended: &[],
+        replacement: b'?',
+    };
+
+    /// Code page 437 of the original IBM PC, which the default libnds font is laid out in.
+    /// Characters not in it are replaced with `■`.
+    pub const CP437: Self = Self {
+        extended: &CP437,
+        replacement: 0xFE,
+    };
+
+    /// Returns the character code of a character.
+    #[inline]
+    pub fn encode(&self, character: char) -> u8 {
+        if character.is_ascii() {
+            return character as u8;
+        }
+
+        let extended = &self.extended[..self.extended.len().min(0x80)];
+        match extended.iter().position(|&entry| entry == character) {
+            Some(index) => 0x80 + index as u8,
+            None => self.replacement,
+        }
+    }
+}
+
+impl Default for CodePage {
+    #[inline]
+    fn default() -> Self {
+        Self::CP437
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_ascii_through() {
+        for code_page in [CodePage::ASCII, CodePage::CP437] {
+            assert_eq!(code_page.encode('A'), b'A');
+            assert_eq!(code_page.encode('~'), b'~');
+            assert_eq!(code_page.encode('\n'), b'\n');
+            assert_eq!(code_page.encode('\0'), 0);
+        }
+    }
+
+    #[test]
+    fn encodes_cp437() {
+        let code_page = CodePage::CP437;
+        assert_eq!(code_page.encode('Ç'), 0x80);
+        assert_eq!(code_page.encode('é'), 0x82);
+        assert_eq!(code_page.encode('░'), 0xB0);
+        assert_eq!(code_page.encode('■'), 0xFE);
+        assert_eq!(code_page.encode('\u{A0}'), 0xFF);
+    }
+
+    #[test]
+    fn replaces_unknown_characters() {
+        assert_eq!(CodePage::CP437.encode('€'), 0xFE);
+        assert_eq!(CodePage::CP437.encode('😀'), 0xFE);
+        assert_eq!(CodePage::ASCII.encode('é'), b'?');
+        assert_eq!(CodePage::ASCII.encode('■'), b'?');
+    }
+
+    #[test]
+    fn encodes_custom_code_pages() {
+        let code_page = CodePage {
+            extended: &['ä', 'ö', 'ü'],
+            replacement: b'#',
+        };
+        assert_eq!(code_page.encode('a'), b'a');
+        assert_eq!(code_page.encode('ü'), 0x82);
+        assert_eq!(code_page.encode('é'), b'#');
+    }
+
+    #[test]
+    fn ignores_characters_past_0xff() {
+        let extended: &'static [char] = [['x'; 0x80].as_slice(), &['é']].concat().leak();
+        let code_page = CodePage {
+            extended,
+            replacement: 0,
+        };
+        assert_eq!(code_page.encode('é'), 0);
+    }
+}
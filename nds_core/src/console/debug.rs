@@ -0,0 +1,26 @@
+use core::fmt::{Result, Write};
+
+/// Maximum length of a single debug message.
+const MESSAGE_LEN: usize = 120;
+
+/// Output to the debug message port of emulators such as no$gba and melonDS.
+///
+/// Output is silently discarded on hardware.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DebugPort;
+
+impl Write for DebugPort {
+    fn write_str(&mut self, s: &str) -> Result {
+        let mut message = [0u8; MESSAGE_LEN + 1];
+
+        for chunk in s.as_bytes().chunks(MESSAGE_LEN) {
+            message[..chunk.len()].copy_from_slice(chunk);
+            message[chunk.len()] = 0;
+
+            // SAFETY: The message is NUL terminated.
+            unsafe { nds_sys::nocashMessage(message.as_ptr().cast()) };
+        }
+
+        Ok(())
+    }
+}
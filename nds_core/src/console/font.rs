@@ -0,0 +1,34 @@
+use super::CodePage;
+use nds_sys::ConsoleFont as RawFont;
+
+/// An 8x8 tile font used to render console text.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsoleFont {
+    /// Tile data of all characters, one 8x8 tile per character.
+    pub tiles: &'static [u16],
+    /// Palette of the font.
+    pub palette: &'static [u16],
+    /// Character code of the first tile.
+    pub first: u16,
+    /// Number of characters in the font.
+    pub count: u16,
+    /// Bits per pixel of the tile data, either 1, 4 or 8.
+    pub bpp: u8,
+    /// Translation of text into the character codes of the font.
+    pub code_page: CodePage,
+}
+
+impl ConsoleFont {
+    /// Converts the font into the libnds representation.
+    pub(crate) fn raw(&self) -> RawFont {
+        RawFont {
+            gfx: self.tiles.as_ptr().cast_mut(),
+            pal: self.palette.as_ptr().cast_mut(),
+            numColors: self.palette.len() as u16,
+            bpp: self.bpp,
+            asciiOffset: self.first,
+            numChars: self.count,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::background::{Size, Type};
+use crate::input::Keys;
+use crate::interrupt::swi_wait_for_vblank;
+use crate::video::Engine;
+use alloc::string::String;
+use core::fmt::Write;
+use core::mem::transmute;
+use core::ptr::null_mut;
+use portable_atomic::{AtomicPtr, Ordering};
+
+/// Key code libnds reports for the backspace key.
+const DVK_BACKSPACE: i32 = 8;
+/// Key code libnds reports for the enter key.
+const DVK_ENTER: i32 = 10;
+/// Key code libnds reports when no key is pressed.
+const NOKEY: i32 = -1;
+
+/// Callback invoked by [`key_pressed`].
+static PRESSED: AtomicPtr<()> = AtomicPtr::new(null_mut());
+/// Callback invoked by [`key_released`].
+static RELEASED: AtomicPtr<()> = AtomicPtr::new(null_mut());
+
+/// A key of the on-screen keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A printable character.
+    Char(char),
+    Backspace,
+    Enter,
+    /// Any other special key, with its libnds key code.
+    Special(i32),
+}
+
+impl Key {
+    #[inline]
+    fn from_raw(code: i32) -> Option<Self> {
+        match code {
+            NOKEY => None,
+            DVK_BACKSPACE => Some(Self::Backspace),
+            DVK_ENTER => Some(Self::Enter),
+            9 | 32..=255 => Some(Self::Char(code as u8 as char)),
+            code => Some(Self::Special(code)),
+        }
+    }
+}
+
+/// The libnds on-screen keyboard for touch based text entry.
+///
+/// libnds only supports a single active keyboard, so creating a new one replaces the previous.
+pub struct Keyboard(*mut nds_sys::Keyboard);
+
+impl Keyboard {
+    /// Initializes the default keyboard on background layer 3 of the sub engine.
+    ///
+    /// The keyboard starts out hidden.
+    #[inline]
+    pub fn demo() -> Self {
+        Self(unsafe { nds_sys::keyboardDemoInit() })
+    }
+
+    /// Initializes the default keyboard on a background of the given engine.
+    ///
+    /// The keyboard uses a 4bpp 256x512 text background and starts out hidden.
+    pub fn new(engine: Engine, layer: i32, map_base: i32, tile_base: i32) -> Self {
+        let main = matches!(engine, Engine::Main);
+        let kind = Type::Text4Bpp as u32;
+        let size = Size::T256x512 as u32;
+
+        // SAFETY: The default keyboard is a static owned by libnds.
+        let raw = unsafe {
+            let raw = nds_sys::keyboardGetDefault();
+            nds_sys::keyboardInit(raw, layer, kind, size, map_base, tile_base, main, true)
+        };
+
+        Self(raw)
+    }
+
+    /// Shows the keyboard, sliding it in from the bottom of the screen.
+    #[inline]
+    pub fn show(&mut self) {
+        unsafe { nds_sys::keyboardShow() };
+    }
+
+    /// Hides the keyboard.
+    #[inline]
+    pub fn hide(&mut self) {
+        unsafe { nds_sys::keyboardHide() };
+    }
+
+    /// Processes touch input and returns the key pressed this frame, if any.
+    ///
+    /// Call this once per frame after [`Keys::scan`].
+    #[inline]
+    pub fn update(&mut self) -> Option<Key> {
+        Key::from_raw(unsafe { nds_sys::keyboardUpdate() })
+    }
+
+    /// Waits until a key is pressed and returns it.
+    #[inline]
+    pub fn get_key(&mut self) -> Key {
+        loop {
+            if let Some(key) = Key::from_raw(unsafe { nds_sys::keyboardGetChar() }) {
+                return key;
+            }
+        }
+    }
+
+    /// Sets a callback invoked whenever a key is pressed.
+    #[inline]
+    pub fn on_key_pressed(&mut self, callback: fn(Key)) {
+        PRESSED.store(callback as *mut (), Ordering::Relaxed);
+        unsafe { (*self.0).OnKeyPressed = Some(key_pressed) };
+    }
+
+    /// Sets a callback invoked whenever a key is released.
+    #[inline]
+    pub fn on_key_released(&mut self, callback: fn(Key)) {
+        RELEASED.store(callback as *mut (), Ordering::Relaxed);
+        unsafe { (*self.0).OnKeyReleased = Some(key_released) };
+    }
+
+    /// Shows the keyboard and reads a line of text into `line` until enter is pressed.
+    ///
+    /// Typed characters are appended to `line` and backspace removes its last character.
+    /// The keyboard is hidden again afterwards.
+    #[inline]
+    pub fn read_line(&mut self, line: &mut String) {
+        self.edit_line(line, None);
+    }
+
+    /// Like [`Keyboard::read_line`], but also echoes the edits to `output`.
+    #[inline]
+    pub fn read_line_echo(&mut self, line: &mut String, output: &mut dyn Write) {
+        self.edit_line(line, Some(output));
+    }
+
+    fn edit_line(&mut self, line: &mut String, mut output: Option<&mut dyn Write>) {
+        self.show();
+
+        loop {
+            swi_wait_for_vblank();
+            Keys::scan();
+
+            let echo = match self.update() {
+                Some(Key::Enter) => break,
+                Some(Key::Char(character)) => {
+                    line.push(character);
+                    Some(character)
+                }
+                Some(Key::Backspace) => line.pop().map(|_| '\x08'),
+                _ => None,
+            };
+
+            if let (Some(output), Some(character)) = (output.as_mut(), echo) {
+                match character {
+                    '\x08' => output.write_str("\x08 \x08").ok(),
+                    character => output.write_char(character).ok(),
+                };
+            }
+        }
+
+        if let Some(output) = output {
+            output.write_char('\n').ok();
+        }
+
+        self.hide();
+    }
+}
+
+extern "C" fn key_pressed(code: i32) {
+    let callback = PRESSED.load(Ordering::Relaxed);
+    if let (false, Some(key)) = (callback.is_null(), Key::from_raw(code)) {
+        // SAFETY: The pointer was stored from a `fn(Key)` by `Keyboard::on_key_pressed`.
+        unsafe { transmute::<*mut (), fn(Key)>(callback)(key) };
+    }
+}
+
+extern "C" fn key_released(code: i32) {
+    let callback = RELEASED.load(Ordering::Relaxed);
+    if let (false, Some(key)) = (callback.is_null(), Key::from_raw(code)) {
+        // SAFETY: The pointer was stored from a `fn(Key)` by `Keyboard::on_key_released`.
+        unsafe { transmute::<*mut (), fn(Key)>(callback)(key) };
+    }
+}
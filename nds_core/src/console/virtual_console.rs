@@ -0,0 +1,453 @@
+use super::{Color, Terminal};
+use core::fmt::{Display, Formatter, Result, Write};
+
+/// Width of the console in characters.
+pub const WIDTH: usize = 32;
+/// Height of the console in characters.
+pub const HEIGHT: usize = 24;
+
+/// Number of columns between tab stops, as used by libnds.
+const TAB_SIZE: usize = 3;
+/// Maximum number of parameters of an escape sequence.
+const MAX_PARAMS: usize = 4;
+
+/// A single character cell of a [`VirtualConsole`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub character: char,
+    pub foreground: Color,
+    pub background: Option<Color>,
+}
+
+impl Default for Cell {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            character: ' ',
+            foreground: Color::White,
+            background: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Text,
+    Escape,
+    Sequence,
+}
+
+/// A console implemented purely in memory with the ANSI subset of the libnds console.
+///
+/// It renders into an inspectable grid of [`Cell`]s, which makes text UIs written against
+/// [`Terminal`] testable without the hardware. [`Display`] prints the characters row by row.
+#[derive(Debug, Clone)]
+pub struct VirtualConsole {
+    cells: [[Cell; WIDTH]; HEIGHT],
+    row: usize,
+    column: usize,
+    saved: (usize, usize),
+    foreground: Color,
+    background: Option<Color>,
+    bright: bool,
+    state: State,
+    params: [u16; MAX_PARAMS],
+    count: usize,
+}
+
+impl VirtualConsole {
+    /// Creates an empty console with the cursor in the top left corner.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            cells: [[Cell::default(); WIDTH]; HEIGHT],
+            row: 0,
+            column: 0,
+            saved: (0, 0),
+            foreground: Color::White,
+            background: None,
+            bright: false,
+            state: State::Text,
+            params: [0; MAX_PARAMS],
+            count: 0,
+        }
+    }
+
+    /// Returns the cell at the given row and column.
+    #[inline]
+    pub fn cell(&self, row: usize, column: usize) -> Cell {
+        self.cells[row][column]
+    }
+
+    /// Returns all cells of a row.
+    #[inline]
+    pub fn row(&self, row: usize) -> &[Cell; WIDTH] {
+        &self.cells[row]
+    }
+
+    /// Returns the zero-based row and column of the cursor.
+    #[inline]
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    /// Processes a single character of output.
+    pub fn put(&mut self, character: char) {
+        match self.state {
+            State::Text => self.text(character),
+            State::Escape => match character {
+                '[' => {
+                    self.params = [0; MAX_PARAMS];
+                    self.count = 0;
+                    self.state = State::Sequence;
+                }
+                _ => self.state = State::Text,
+            },
+            State::Sequence => match character {
+                '0'..='9' => {
+                    self.count = self.count.max(1);
+                    let param = &mut self.params[self.count - 1];
+                    let digit = character as u16 - '0' as u16;
+                    *param = param.saturating_mul(10).saturating_add(digit);
+                }
+                ';' => self.count = (self.count.max(1) + 1).min(MAX_PARAMS),
+                _ => {
+                    self.state = State::Text;
+                    self.sequence(character);
+                }
+            },
+        }
+    }
+
+    fn text(&mut self, character: char) {
+        match character {
+            '\x1b' => self.state = State::Escape,
+            '\n' => self.newline(),
+            '\r' => self.column = 0,
+            '\t' => {
+                let column = (self.column / TAB_SIZE + 1) * TAB_SIZE;
+                match column < WIDTH {
+                    true => self.column = column,
+                    false => self.newline(),
+                }
+            }
+            '\x08' => {
+                match (self.column, self.row) {
+                    (0, 0) => {}
+                    (0, _) => (self.row, self.column) = (self.row - 1, WIDTH - 1),
+                    _ => self.column -= 1,
+                }
+                self.cells[self.row][self.column] = self.blank();
+            }
+            _ => {
+                if self.column >= WIDTH {
+                    self.newline();
+                }
+
+                self.cells[self.row][self.column] = Cell {
+                    character,
+                    ..self.blank()
+                };
+                self.column += 1;
+            }
+        }
+    }
+
+    fn sequence(&mut self, command: char) {
+        let params = self.params;
+        let param = |index: usize| params[index] as usize;
+        // Relative movements default to a distance of one.
+        let distance = param(0).max(1);
+
+        match command {
+            'H' | 'f' => {
+                self.row = param(0).min(HEIGHT - 1);
+                self.column = param(1).min(WIDTH - 1);
+            }
+            'A' => self.row = self.row.saturating_sub(distance),
+            'B' => self.row = (self.row + distance).min(HEIGHT - 1),
+            'C' => self.column = (self.column + distance).min(WIDTH - 1),
+            'D' => self.column = self.column.saturating_sub(distance),
+            's' => self.saved = (self.row, self.column),
+            'u' => (self.row, self.column) = self.saved,
+            'J' if param(0) == 2 => {
+                self.cells = [[self.blank(); WIDTH]; HEIGHT];
+                (self.row, self.column) = (0, 0);
+            }
+            'K' => {
+                let blank = self.blank();
+                let line = &mut self.cells[self.row];
+                let column = self.column.min(WIDTH);
+                match param(0) {
+                    0 => line[column..].fill(blank),
+                    1 => line[..(column + 1).min(WIDTH)].fill(blank),
+                    _ => line.fill(blank),
+                }
+            }
+            'm' => {
+                for index in 0..self.count.max(1) {
+                    match param(index) {
+                        0 => {
+                            self.foreground = Color::White;
+                            self.background = None;
+                            self.bright = false;
+                        }
+                        1 => self.bright = true,
+                        2 | 22 => self.bright = false,
+                        code @ 30..=37 => {
+                            self.foreground = Color::from_ansi(code as u8 - 30, false)
+                        }
+                        39 => self.foreground = Color::White,
+                        code @ 40..=47 => {
+                            self.background = Some(Color::from_ansi(code as u8 - 40, false))
+                        }
+                        49 => self.background = None,
+                        _ => {}
+                    }
+                }
+
+                // Intensity applies to the foreground color regardless of parameter order.
+                let (code, _) = self.foreground.ansi();
+                if code != 7 {
+                    self.foreground = Color::from_ansi(code, self.bright);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn newline(&mut self) {
+        self.column = 0;
+        match self.row + 1 < HEIGHT {
+            true => self.row += 1,
+            false => {
+                self.cells.copy_within(1.., 0);
+                self.cells[HEIGHT - 1] = [self.blank(); WIDTH];
+            }
+        }
+    }
+
+    /// Returns an empty cell with the current colors.
+    #[inline]
+    fn blank(&self) -> Cell {
+        Cell {
+            character: ' ',
+            foreground: self.foreground,
+            background: self.background,
+        }
+    }
+}
+
+impl Default for VirtualConsole {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for VirtualConsole {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result {
+        s.chars().for_each(|character| self.put(character));
+        Ok(())
+    }
+}
+
+impl Terminal for VirtualConsole {}
+
+impl Display for VirtualConsole {
+    /// Prints the characters of every row without trailing spaces, separated by newlines.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (index, row) in self.cells.iter().enumerate() {
+            let end = row.iter().rposition(|cell| cell.character != ' ');
+            for cell in &row[..end.map_or(0, |end| end + 1)] {
+                f.write_char(cell.character)?;
+            }
+
+            if index + 1 < HEIGHT {
+                f.write_char('\n')?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Styled;
+    use super::*;
+
+    fn console(text: &str) -> VirtualConsole {
+        let mut console = VirtualConsole::new();
+        console.write_str(text).unwrap();
+        console
+    }
+
+    fn line(console: &VirtualConsole, row: usize) -> String {
+        let text: String = console.row(row).iter().map(|cell| cell.character).collect();
+        text.trim_end().into()
+    }
+
+    #[test]
+    fn wraps_at_last_column() {
+        let text = "a".repeat(WIDTH);
+        let mut console = console(&text);
+        assert_eq!(console.cursor(), (0, WIDTH));
+
+        console.write_str("b").unwrap();
+        assert_eq!(line(&console, 0), text);
+        assert_eq!(line(&console, 1), "b");
+        assert_eq!(console.cursor(), (1, 1));
+    }
+
+    #[test]
+    fn scrolls_at_last_row() {
+        let mut console = VirtualConsole::new();
+        for row in 0..HEIGHT {
+            writeln!(console, "{row}").unwrap();
+        }
+
+        assert_eq!(console.cursor(), (HEIGHT - 1, 0));
+        assert_eq!(line(&console, 0), "1");
+        assert_eq!(line(&console, HEIGHT - 2), "23");
+        assert_eq!(line(&console, HEIGHT - 1), "");
+    }
+
+    #[test]
+    fn carriage_return_and_newline() {
+        let console = console("abc\rx\ny");
+        assert_eq!(line(&console, 0), "xbc");
+        assert_eq!(line(&console, 1), "y");
+    }
+
+    #[test]
+    fn moves_cursor_absolute() {
+        let mut console = console("\x1b[2;5Hx");
+        assert_eq!(console.cell(2, 5).character, 'x');
+
+        console.write_str("\x1b[99;99H").unwrap();
+        assert_eq!(console.cursor(), (HEIGHT - 1, WIDTH - 1));
+
+        console.write_str("\x1b[H").unwrap();
+        assert_eq!(console.cursor(), (0, 0));
+    }
+
+    #[test]
+    fn moves_cursor_relative() {
+        let mut console = console("\x1b[10;10H");
+
+        console.write_str("\x1b[3A").unwrap();
+        assert_eq!(console.cursor(), (7, 10));
+        console.write_str("\x1b[B").unwrap();
+        assert_eq!(console.cursor(), (8, 10));
+        console.write_str("\x1b[4C").unwrap();
+        assert_eq!(console.cursor(), (8, 14));
+        console.write_str("\x1b[20D").unwrap();
+        assert_eq!(console.cursor(), (8, 0));
+        console.write_str("\x1b[99B\x1b[99C").unwrap();
+        assert_eq!(console.cursor(), (HEIGHT - 1, WIDTH - 1));
+    }
+
+    #[test]
+    fn terminal_helpers() {
+        let mut console = VirtualConsole::new();
+        console.set_cursor(4, 6);
+        console.move_cursor(-2, 3);
+        assert_eq!(console.cursor(), (2, 9));
+
+        console.save_cursor();
+        console.set_cursor(0, 0);
+        console.restore_cursor();
+        assert_eq!(console.cursor(), (2, 9));
+    }
+
+    #[test]
+    fn clears_lines() {
+        let mut console = console("abcdef\x1b[0;3H\x1b[K");
+        assert_eq!(line(&console, 0), "abc");
+
+        console.write_str("\x1b[0;1H\x1b[1K").unwrap();
+        assert_eq!(line(&console, 0), "  c");
+
+        console.write_str("\x1b[2K").unwrap();
+        assert_eq!(line(&console, 0), "");
+    }
+
+    #[test]
+    fn clears_screen() {
+        let mut console = console("abc\ndef");
+        console.clear_screen();
+
+        assert_eq!(console.cursor(), (0, 0));
+        assert_eq!(console.to_string(), "\n".repeat(HEIGHT - 1));
+    }
+
+    #[test]
+    fn sets_colors() {
+        let console = console("\x1b[31;44ma\x1b[1mb\x1b[0mc\x1b[1;32md\x1b[39;49me");
+
+        assert_eq!(console.cell(0, 0).foreground, Color::Red);
+        assert_eq!(console.cell(0, 0).background, Some(Color::Blue));
+        assert_eq!(console.cell(0, 1).foreground, Color::RedLight);
+        assert_eq!(
+            console.cell(0, 2),
+            Cell {
+                character: 'c',
+                ..Cell::default()
+            }
+        );
+        assert_eq!(console.cell(0, 3).foreground, Color::GreenLight);
+        assert_eq!(console.cell(0, 4).foreground, Color::White);
+        assert_eq!(console.cell(0, 4).background, None);
+    }
+
+    #[test]
+    fn styled_round_trip() {
+        let styled = Styled::new("x")
+            .foreground(Color::CyanLight)
+            .background(Color::Red);
+        let console = console(&format!("{styled}y"));
+
+        assert_eq!(console.cell(0, 0).foreground, Color::CyanLight);
+        assert_eq!(console.cell(0, 0).background, Some(Color::Red));
+        assert_eq!(
+            console.cell(0, 1),
+            Cell {
+                character: 'y',
+                ..Cell::default()
+            }
+        );
+    }
+
+    #[test]
+    fn backspace() {
+        let mut console = console("ab\x08");
+        assert_eq!(console.cursor(), (0, 1));
+        assert_eq!(line(&console, 0), "a");
+
+        console.write_str("\n\x08").unwrap();
+        assert_eq!(console.cursor(), (0, WIDTH - 1));
+
+        console.write_str("\x1b[H\x08").unwrap();
+        assert_eq!(console.cursor(), (0, 0));
+    }
+
+    #[test]
+    fn tab_stops() {
+        let mut console = console("\tx\t");
+        assert_eq!(console.cell(0, TAB_SIZE).character, 'x');
+        assert_eq!(console.cursor(), (0, TAB_SIZE * 2));
+
+        console.write_str("\x1b[0;30H\t").unwrap();
+        assert_eq!(console.cursor(), (1, 0));
+    }
+
+    #[test]
+    fn display_trims_rows() {
+        let console = console("a  \n b");
+        let text = console.to_string();
+        assert!(text.starts_with("a\n b\n"));
+        assert_eq!(text.split('\n').count(), HEIGHT);
+    }
+}
@@ -0,0 +1,290 @@
+//! Encoders for 15-bit `BGR555` bitmaps.
+//!
+//! The encoders only operate on pixel data, so they can be used outside of the hardware as well.
+
+use alloc::vec::Vec;
+
+/// Encodes a `BGR555` bitmap in row-major order as an uncompressed 24-bit BMP image.
+///
+/// # Panics
+/// Panics if either dimension is zero or the pixel count does not match the dimensions.
+pub fn encode_bmp(width: usize, height: usize, pixels: &[u16]) -> Vec<u8> {
+    check(width, height, pixels);
+
+    let stride = (width * 3).next_multiple_of(4);
+    let data = stride * height;
+    let size = 54 + data;
+
+    let mut out = Vec::with_capacity(size);
+    out.extend_from_slice(b"BM");
+    out.extend_from_slice(&(size as u32).to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&54u32.to_le_bytes());
+
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&(height as i32).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&24u16.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&(data as u32).to_le_bytes());
+    out.extend_from_slice(&2835i32.to_le_bytes());
+    out.extend_from_slice(&2835i32.to_le_bytes());
+    out.extend_from_slice(&[0; 8]);
+
+    // BMP rows are stored bottom-up in BGR order.
+    for row in pixels.chunks_exact(width).rev() {
+        for &pixel in row {
+            let [r, g, b] = rgb888(pixel);
+            out.extend_from_slice(&[b, g, r]);
+        }
+        out.resize(out.len() + stride - width * 3, 0);
+    }
+
+    out
+}
+
+/// Encodes a `BGR555` bitmap in row-major order as a 24-bit PNG image.
+///
+/// The image data is stored without compression to keep the encoder small and fast.
+///
+/// # Panics
+/// Panics if either dimension is zero or the pixel count does not match the dimensions.
+pub fn encode_png(width: usize, height: usize, pixels: &[u16]) -> Vec<u8> {
+    check(width, height, pixels);
+
+    let mut raw = Vec::with_capacity((width * 3 + 1) * height);
+    for row in pixels.chunks_exact(width) {
+        raw.push(0);
+        row.iter()
+            .for_each(|&pixel| raw.extend_from_slice(&rgb888(pixel)));
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut out = Vec::from(*b"\x89PNG\r\n\x1a\n");
+    png_chunk(&mut out, b"IHDR", &header);
+    png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Expands a `BGR555` pixel to 8 bits per channel.
+#[inline]
+pub fn rgb888(pixel: u16) -> [u8; 3] {
+    let expand = |channel: u16| {
+        let channel = (channel & 0x1F) as u8;
+        channel << 3 | channel >> 2
+    };
+
+    [expand(pixel), expand(pixel >> 5), expand(pixel >> 10)]
+}
+
+#[inline]
+fn check(width: usize, height: usize, pixels: &[u16]) {
+    assert!(width > 0 && height > 0, "Dimensions must not be zero");
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "Pixel count must match the dimensions"
+    );
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = u16::MAX as usize;
+
+    let mut out = Vec::with_capacity(data.len() + data.len() / BLOCK * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+
+    let mut blocks = data.chunks(BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        out.push(blocks.peek().is_none() as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (a, b) = data
+        .chunks(5552)
+        .fold((1u32, 0u32), |(mut a, mut b), chunk| {
+            for &byte in chunk {
+                a += byte as u32;
+                b += a;
+            }
+            (a % MOD, b % MOD)
+        });
+
+    b << 16 | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut n = 0;
+        while n < 256 {
+            let mut c = n as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = match c & 1 {
+                    1 => 0xEDB8_8320 ^ (c >> 1),
+                    _ => c >> 1,
+                };
+                k += 1;
+            }
+            table[n] = c;
+            n += 1;
+        }
+        table
+    };
+
+    !data.iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: u16 = 0x7FFF;
+    const RED: u16 = 0x001F;
+    const BLUE: u16 = 0x7C00;
+
+    fn u32_le(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    fn u32_be(bytes: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn rgb888_expands_channels() {
+        assert_eq!(rgb888(0), [0, 0, 0]);
+        assert_eq!(rgb888(WHITE), [255, 255, 255]);
+        assert_eq!(rgb888(RED), [255, 0, 0]);
+        assert_eq!(rgb888(BLUE), [0, 0, 255]);
+        assert_eq!(rgb888(0x10 << 5), [0, 132, 0]);
+    }
+
+    #[test]
+    fn bmp_header() {
+        let bmp = encode_bmp(2, 2, &[WHITE; 4]);
+
+        assert_eq!(&bmp[0..2], b"BM");
+        assert_eq!(u32_le(&bmp, 2) as usize, bmp.len());
+        assert_eq!(u32_le(&bmp, 10), 54);
+        assert_eq!(u32_le(&bmp, 14), 40);
+        assert_eq!(u32_le(&bmp, 18), 2);
+        assert_eq!(u32_le(&bmp, 22), 2);
+        assert_eq!(&bmp[26..30], &[1, 0, 24, 0]);
+        assert_eq!(u32_le(&bmp, 30), 0);
+        assert_eq!(u32_le(&bmp, 34), 16);
+    }
+
+    #[test]
+    fn bmp_pads_odd_rows() {
+        // Rows are 3 pixels of 3 bytes, padded to 12 bytes and stored bottom-up.
+        let bmp = encode_bmp(3, 2, &[RED, RED, RED, BLUE, BLUE, BLUE]);
+        let data = &bmp[54..];
+
+        assert_eq!(bmp.len(), 54 + 24);
+        assert_eq!(u32_le(&bmp, 34), 24);
+        assert_eq!(&data[..12], &[255, 0, 0, 255, 0, 0, 255, 0, 0, 0, 0, 0]);
+        assert_eq!(&data[12..], &[0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn png_header_and_chunks() {
+        let png = encode_png(3, 1, &[RED, WHITE, BLUE]);
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(u32_be(&png, 8), 13);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32_be(&png, 16), 3);
+        assert_eq!(u32_be(&png, 20), 1);
+        assert_eq!(&png[24..29], &[8, 2, 0, 0, 0]);
+        assert_eq!(u32_be(&png, 29), crc32(&png[12..29]));
+
+        let idat = u32_be(&png, 33) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let zlib = &png[41..41 + idat];
+        let raw = [0, 255, 0, 0, 255, 255, 255, 0, 0, 255];
+        assert_eq!(&zlib[..2], &[0x78, 0x01]);
+        assert_eq!(&zlib[2..7], &[1, 10, 0, 0xF5, 0xFF]);
+        assert_eq!(&zlib[7..17], &raw);
+        assert_eq!(u32_be(zlib, 17), adler32(&raw));
+
+        assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xAE\x42\x60\x82");
+    }
+
+    #[test]
+    fn crc32_vectors() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    }
+
+    #[test]
+    fn adler32_vectors() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(&[0xFF; 6000]), 0xA497_59EA);
+    }
+
+    #[test]
+    fn zlib_stored_empty() {
+        assert_eq!(
+            zlib_stored(&[]),
+            [0x78, 0x01, 1, 0, 0, 0xFF, 0xFF, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn zlib_stored_splits_blocks() {
+        let data = [7; u16::MAX as usize + 1];
+        let zlib = zlib_stored(&data);
+        let second = 2 + 5 + u16::MAX as usize;
+
+        assert_eq!(&zlib[2..7], &[0, 0xFF, 0xFF, 0, 0]);
+        assert_eq!(&zlib[second..second + 5], &[1, 1, 0, 0xFE, 0xFF]);
+        assert_eq!(zlib.len(), data.len() + 2 + 5 * 2 + 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dimensions must not be zero")]
+    fn rejects_zero_dimensions() {
+        encode_png(0, 0, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Pixel count must match the dimensions")]
+    fn rejects_wrong_pixel_count() {
+        encode_bmp(2, 2, &[0; 3]);
+    }
+}
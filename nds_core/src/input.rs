@@ -1,44 +1,87 @@
 //! Physical input related APIs.
 
-use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
-use nds_sys::KEYPAD_BITS;
+mod action;
+mod gestures;
+#[cfg(feature = "sys")]
+mod key_interrupt;
+#[cfg(feature = "sys")]
+mod lid;
+mod record;
+mod state;
+mod touch;
 
+pub use action::*;
+pub use gestures::*;
+#[cfg(feature = "sys")]
+pub use key_interrupt::*;
+#[cfg(feature = "sys")]
+pub use lid::*;
+pub use record::*;
+pub use state::*;
+pub use touch::*;
+
+use core::fmt::{Display, Formatter, Result};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+/// Every button with its name, in bit order.
+const NAMES: [(Keys, &str); 15] = [
+    (Keys::A, "A"),
+    (Keys::B, "B"),
+    (Keys::SELECT, "SELECT"),
+    (Keys::START, "START"),
+    (Keys::RIGHT, "RIGHT"),
+    (Keys::LEFT, "LEFT"),
+    (Keys::UP, "UP"),
+    (Keys::DOWN, "DOWN"),
+    (Keys::R, "R"),
+    (Keys::L, "L"),
+    (Keys::X, "X"),
+    (Keys::Y, "Y"),
+    (Keys::TOUCH, "TOUCH"),
+    (Keys::LID, "LID"),
+    (Keys::DEBUG, "DEBUG"),
+];
+
+/// A set of keys, with the bits of `KEYINPUT` and the touchscreen, lid and debug bits of libnds.
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Keys(KEYPAD_BITS);
+pub struct Keys(u32);
 
 impl Keys {
     /// Keypad `A` button.
-    pub const A: Self = Self(KEYPAD_BITS::KEY_A);
+    pub const A: Self = Self(1 << 0);
     /// Keypad `B` button.
-    pub const B: Self = Self(KEYPAD_BITS::KEY_B);
+    pub const B: Self = Self(1 << 1);
     /// Keypad `SELECT` button.
-    pub const SELECT: Self = Self(KEYPAD_BITS::KEY_SELECT);
+    pub const SELECT: Self = Self(1 << 2);
     /// Keypad `START` button.
-    pub const START: Self = Self(KEYPAD_BITS::KEY_START);
+    pub const START: Self = Self(1 << 3);
     /// Keypad `RIGHT` button.
-    pub const RIGHT: Self = Self(KEYPAD_BITS::KEY_RIGHT);
+    pub const RIGHT: Self = Self(1 << 4);
     /// Keypad `LEFT` button.
-    pub const LEFT: Self = Self(KEYPAD_BITS::KEY_LEFT);
+    pub const LEFT: Self = Self(1 << 5);
     /// Keypad `UP` button.
-    pub const UP: Self = Self(KEYPAD_BITS::KEY_UP);
+    pub const UP: Self = Self(1 << 6);
     /// Keypad `DOWN` button.
-    pub const DOWN: Self = Self(KEYPAD_BITS::KEY_DOWN);
+    pub const DOWN: Self = Self(1 << 7);
     /// Keypad `R` (right shoulder) button.
-    pub const R: Self = Self(KEYPAD_BITS::KEY_R);
+    pub const R: Self = Self(1 << 8);
     /// Keypad `L` (left shoulder) button.
-    pub const L: Self = Self(KEYPAD_BITS::KEY_L);
+    pub const L: Self = Self(1 << 9);
     /// Keypad `X` button.
-    pub const X: Self = Self(KEYPAD_BITS::KEY_X);
+    pub const X: Self = Self(1 << 10);
     /// Keypad `Y` button.
-    pub const Y: Self = Self(KEYPAD_BITS::KEY_Y);
+    pub const Y: Self = Self(1 << 11);
     /// Touchscreen `TOUCH` pendown.
-    pub const TOUCH: Self = Self(KEYPAD_BITS::KEY_TOUCH);
+    pub const TOUCH: Self = Self(1 << 12);
     /// Handheld `LID` state.
-    pub const LID: Self = Self(KEYPAD_BITS::KEY_LID);
+    pub const LID: Self = Self(1 << 13);
     /// Debug `DEBUG` button.
-    pub const DEBUG: Self = Self(KEYPAD_BITS::KEY_DEBUG);
+    pub const DEBUG: Self = Self(1 << 14);
+}
 
+#[cfg(feature = "sys")]
+impl Keys {
     /// Obtains the current keypad state.
     ///
     /// Call this function once per main loop in order to use the keypad functions.
@@ -52,19 +95,213 @@ impl Keys {
     #[inline(always)]
     pub fn down() -> Self {
         // SAFETY: todo!()
-        unsafe { Self(KEYPAD_BITS(nds_sys::keysDown())) }
+        unsafe { Self(nds_sys::keysDown()) }
     }
 
     /// Obtains the current keypad held state.
     #[inline(always)]
     pub fn held() -> Self {
         // SAFETY: todo!()
-        unsafe { Self(KEYPAD_BITS(nds_sys::keysHeld())) }
+        unsafe { Self(nds_sys::keysHeld()) }
     }
 
+    /// Obtains the keys that have been released right now.
+    #[inline(always)]
+    pub fn up() -> Self {
+        // SAFETY: Reading the keypad state has no side effects.
+        unsafe { Self(nds_sys::keysUp()) }
+    }
+
+    /// Obtains the keys that have been pressed right now, or are repeating while held.
+    ///
+    /// The repeat timing is set with [`Keys::set_repeat`].
+    #[inline(always)]
+    pub fn down_repeat() -> Self {
+        // SAFETY: Reading the keypad state has no side effects.
+        unsafe { Self(nds_sys::keysDownRepeat()) }
+    }
+
+    /// Sets the number of scans a key has to be held before it starts repeating,
+    /// and the number of scans between repeats.
+    #[inline(always)]
+    pub fn set_repeat(delay: u8, rate: u8) {
+        unsafe { nds_sys::keysSetRepeat(delay, rate) };
+    }
+}
+
+impl Keys {
+    /// Returns a set without any keys.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns a set with every key.
+    #[inline]
+    pub const fn all() -> Self {
+        Self(0x7FFF)
+    }
+
+    /// Returns whether no key is set.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether any of the keys of `rhs` is set.
+    #[inline]
+    pub const fn intersects(self, rhs: Keys) -> bool {
+        (self.0 & rhs.0) != 0
+    }
+
+    /// Returns whether all of the keys of `rhs` are set.
+    #[inline]
+    pub const fn contains_all(self, rhs: Keys) -> bool {
+        (self.0 & rhs.0) == rhs.0
+    }
+
+    #[deprecated = "Returns true on any overlap, use `intersects` or `contains_all` instead"]
     #[inline]
     pub fn contains(self, rhs: Keys) -> bool {
-        (self.0.0 & rhs.0.0) != 0
+        self.intersects(rhs)
+    }
+
+    /// Sets or clears the keys of `keys`.
+    #[inline]
+    pub fn set(&mut self, keys: Keys, value: bool) {
+        match value {
+            true => *self |= keys,
+            false => *self &= !keys,
+        }
+    }
+
+    /// Returns an iterator over the individual keys that are set.
+    #[inline]
+    pub fn iter(self) -> KeysIter {
+        KeysIter {
+            keys: self,
+            index: 0,
+        }
+    }
+
+    /// Returns the name of a single key, or `None` for sets of multiple or no keys.
+    #[inline]
+    pub fn name(self) -> Option<&'static str> {
+        NAMES
+            .iter()
+            .find(|(key, _)| *key == self)
+            .map(|&(_, name)| name)
+    }
+}
+
+#[cfg(feature = "sys")]
+const _: () = {
+    use nds_sys::KEYPAD_BITS;
+
+    let keys = [
+        KEYPAD_BITS::KEY_A,
+        KEYPAD_BITS::KEY_B,
+        KEYPAD_BITS::KEY_SELECT,
+        KEYPAD_BITS::KEY_START,
+        KEYPAD_BITS::KEY_RIGHT,
+        KEYPAD_BITS::KEY_LEFT,
+        KEYPAD_BITS::KEY_UP,
+        KEYPAD_BITS::KEY_DOWN,
+        KEYPAD_BITS::KEY_R,
+        KEYPAD_BITS::KEY_L,
+        KEYPAD_BITS::KEY_X,
+        KEYPAD_BITS::KEY_Y,
+        KEYPAD_BITS::KEY_TOUCH,
+        KEYPAD_BITS::KEY_LID,
+        KEYPAD_BITS::KEY_DEBUG,
+    ];
+
+    let mut index = 0;
+    while index < keys.len() {
+        assert!(
+            NAMES[index].0.0 == keys[index].0,
+            "Keys must match the libnds key bits"
+        );
+        index += 1;
+    }
+};
+
+impl Default for Keys {
+    #[inline]
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// An iterator over the individual keys of a [`Keys`] set.
+#[derive(Debug, Clone)]
+pub struct KeysIter {
+    keys: Keys,
+    index: usize,
+}
+
+impl Iterator for KeysIter {
+    type Item = Keys;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(key, _)) = NAMES.get(self.index) {
+            self.index += 1;
+            if self.keys.intersects(key) {
+                return Some(key);
+            }
+        }
+
+        None
+    }
+}
+
+impl IntoIterator for Keys {
+    type Item = Keys;
+    type IntoIter = KeysIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Display for Keys {
+    /// Writes the names of all set keys separated by `+`, such as `L+R`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for (index, key) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str("+")?;
+            }
+            f.write_str(key.name().unwrap_or_default())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Not for Keys {
+    type Output = Self;
+
+    /// Returns all keys not set, leaving unused bits clear.
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::all().0)
+    }
+}
+
+impl BitXor for Keys {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl BitXorAssign for Keys {
+    #[inline]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
     }
 }
 
@@ -0,0 +1,376 @@
+use super::{DecodeError, InputState, Keys};
+use alloc::vec::Vec;
+
+/// Magic bytes at the start of a serialized action map.
+const MAGIC: &[u8; 4] = b"NDSA";
+/// Version of the serialization format.
+const VERSION: u8 = 1;
+
+/// A rectangular region of the touchscreen in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Region {
+    /// Creates a region from its top left corner and size.
+    #[inline]
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns whether the region contains a position.
+    #[inline]
+    pub const fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+/// An input that triggers an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    /// A combination of keys that all have to be held.
+    Keys(Keys),
+    /// A region of the touchscreen that has to be touched.
+    Touch(Region),
+}
+
+impl Binding {
+    /// Returns whether the binding is active in the given state.
+    #[inline]
+    pub fn is_active(&self, state: &InputState) -> bool {
+        match self {
+            Self::Keys(keys) => !keys.is_empty() && state.held.contains_all(*keys),
+            Self::Touch(region) => {
+                state.touch.down && region.contains(state.touch.x, state.touch.y)
+            }
+        }
+    }
+}
+
+/// A mapping of game actions to remappable bindings.
+///
+/// Every action can have any number of bindings and is held while any of them is active.
+/// Call [`ActionMap::update`] once per frame before querying actions.
+///
+/// # Examples
+/// ```
+/// use nds_core::input::{ActionMap, Binding, Keys, Region};
+///
+/// #[derive(Clone, Copy, PartialEq, Eq)]
+/// enum Action {
+///     Jump,
+///     Pause,
+/// }
+///
+/// let mut actions = ActionMap::new();
+/// actions.bind(Action::Jump, Binding::Keys(Keys::A));
+/// actions.bind(Action::Jump, Binding::Touch(Region::new(0, 160, 64, 32)));
+/// actions.bind(Action::Pause, Binding::Keys(Keys::START));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ActionMap<A> {
+    bindings: Vec<(A, Binding)>,
+    held: Vec<A>,
+    previous: Vec<A>,
+}
+
+impl<A: Copy + PartialEq> ActionMap<A> {
+    /// Creates a map without any bindings.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            held: Vec::new(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Adds a binding to an action, in addition to its existing bindings.
+    #[inline]
+    pub fn bind(&mut self, action: A, binding: Binding) {
+        if !self.bindings.contains(&(action, binding)) {
+            self.bindings.push((action, binding));
+        }
+    }
+
+    /// Removes all bindings of an action.
+    #[inline]
+    pub fn unbind(&mut self, action: A) {
+        self.bindings.retain(|&(bound, _)| bound != action);
+    }
+
+    /// Replaces all bindings of an action with a single binding.
+    #[inline]
+    pub fn rebind(&mut self, action: A, binding: Binding) {
+        self.unbind(action);
+        self.bind(action, binding);
+    }
+
+    /// Returns the bindings of an action.
+    #[inline]
+    pub fn bindings(&self, action: A) -> impl Iterator<Item = Binding> + '_ {
+        let bindings = self.bindings.iter();
+        bindings.filter_map(move |&(bound, binding)| (bound == action).then_some(binding))
+    }
+
+    /// Updates the actions from the input of a new frame.
+    pub fn update(&mut self, state: &InputState) {
+        core::mem::swap(&mut self.held, &mut self.previous);
+        self.held.clear();
+
+        for &(action, binding) in &self.bindings {
+            if !self.held.contains(&action) && binding.is_active(state) {
+                self.held.push(action);
+            }
+        }
+    }
+
+    /// Returns whether an action is held this frame.
+    #[inline]
+    pub fn held(&self, action: A) -> bool {
+        self.held.contains(&action)
+    }
+
+    /// Returns whether an action started being held this frame.
+    #[inline]
+    pub fn pressed(&self, action: A) -> bool {
+        self.held.contains(&action) && !self.previous.contains(&action)
+    }
+
+    /// Returns whether an action stopped being held this frame.
+    #[inline]
+    pub fn released(&self, action: A) -> bool {
+        !self.held.contains(&action) && self.previous.contains(&action)
+    }
+
+    /// Serializes the bindings, with actions stored as their `u8` value.
+    ///
+    /// The format is the magic bytes `NDSA` and a version byte, followed by one entry per
+    /// binding: the action, a kind byte, and then either the keys as a little endian `u16` for
+    /// kind 0, or the region as four little endian `u16`s `x`, `y`, `width` and `height` for kind 1.
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        A: Into<u8>,
+    {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + self.bindings.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+
+        for &(action, binding) in &self.bindings {
+            bytes.push(action.into());
+            match binding {
+                Binding::Keys(keys) => {
+                    bytes.push(0);
+                    bytes.extend_from_slice(&(keys.0 as u16).to_le_bytes());
+                }
+                Binding::Touch(region) => {
+                    bytes.push(1);
+                    for value in [region.x, region.y, region.width, region.height] {
+                        bytes.extend_from_slice(&value.to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// Deserializes bindings written by [`ActionMap::to_bytes`].
+    ///
+    /// Unknown actions, kinds and key bits are rejected as [`DecodeError::Invalid`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        A: TryFrom<u8>,
+    {
+        let bytes = bytes.strip_prefix(MAGIC).ok_or(DecodeError::Magic)?;
+        let (&version, mut bytes) = bytes.split_first().ok_or(DecodeError::Truncated)?;
+        if version != VERSION {
+            return Err(DecodeError::Version(version));
+        }
+
+        let mut map = Self::new();
+        while !bytes.is_empty() {
+            let mut take = || match bytes.split_first_chunk::<2>() {
+                Some((head, tail)) => {
+                    bytes = tail;
+                    Ok(*head)
+                }
+                None => Err(DecodeError::Truncated),
+            };
+
+            let [action, kind] = take()?;
+            let action = A::try_from(action).map_err(|_| DecodeError::Invalid)?;
+            let binding = match kind {
+                0 => match Keys(u16::from_le_bytes(take()?) as _) {
+                    keys if !Keys::all().contains_all(keys) => return Err(DecodeError::Invalid),
+                    keys => Binding::Keys(keys),
+                },
+                1 => {
+                    let mut value = || take().map(u16::from_le_bytes);
+                    Binding::Touch(Region::new(value()?, value()?, value()?, value()?))
+                }
+                _ => return Err(DecodeError::Invalid),
+            };
+
+            map.bind(action, binding);
+        }
+
+        Ok(map)
+    }
+}
+
+impl<A: Copy + PartialEq> Default for ActionMap<A> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputFrame;
+
+    const HEADER: &[u8] = b"NDSA\x01";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        Jump,
+        Pause,
+    }
+
+    impl From<Action> for u8 {
+        fn from(action: Action) -> Self {
+            action as u8
+        }
+    }
+
+    impl TryFrom<u8> for Action {
+        type Error = ();
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            match value {
+                0 => Ok(Self::Jump),
+                1 => Ok(Self::Pause),
+                _ => Err(()),
+            }
+        }
+    }
+
+    fn actions() -> ActionMap<Action> {
+        let mut actions = ActionMap::new();
+        actions.bind(Action::Jump, Binding::Keys(Keys::A));
+        actions.bind(Action::Jump, Binding::Touch(Region::new(0, 160, 64, 32)));
+        actions.bind(Action::Pause, Binding::Keys(Keys::L | Keys::R));
+        actions
+    }
+
+    fn decode(entries: &[u8]) -> Result<ActionMap<Action>, DecodeError> {
+        ActionMap::from_bytes(&[HEADER, entries].concat())
+    }
+
+    #[test]
+    fn round_trip() {
+        let actions = actions();
+        let decoded = ActionMap::<Action>::from_bytes(&actions.to_bytes()).unwrap();
+
+        for action in [Action::Jump, Action::Pause] {
+            assert!(decoded.bindings(action).eq(actions.bindings(action)));
+        }
+    }
+
+    #[test]
+    fn encodes_bindings() {
+        let mut actions = ActionMap::new();
+        actions.bind(Action::Pause, Binding::Keys(Keys::START | Keys::A));
+        actions.bind(Action::Jump, Binding::Touch(Region::new(1, 2, 300, 4)));
+
+        let entries: &[u8] = &[1, 0, 0x09, 0x00, 0, 1, 1, 0, 2, 0, 0x2C, 0x01, 4, 0];
+        assert_eq!(actions.to_bytes(), [HEADER, entries].concat());
+    }
+
+    #[test]
+    fn rejects_header() {
+        let decode = ActionMap::<Action>::from_bytes;
+        assert_eq!(decode(b"NDSI\x01").err(), Some(DecodeError::Magic));
+        assert_eq!(decode(b"NDSA").err(), Some(DecodeError::Truncated));
+        assert_eq!(decode(b"NDSA\x02").err(), Some(DecodeError::Version(2)));
+        assert!(
+            decode(HEADER)
+                .unwrap()
+                .bindings(Action::Jump)
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        let bytes = actions().to_bytes();
+        // Entries end after 4, 14 and 18 bytes.
+        let boundaries = [4, 14].map(|len| HEADER.len() + len);
+        for len in (HEADER.len() + 1..bytes.len()).filter(|len| !boundaries.contains(len)) {
+            assert_eq!(
+                ActionMap::<Action>::from_bytes(&bytes[..len]).err(),
+                Some(DecodeError::Truncated),
+                "{len} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        assert_eq!(decode(&[0, 2, 0, 0]).err(), Some(DecodeError::Invalid));
+        assert_eq!(decode(&[2, 0, 1, 0]).err(), Some(DecodeError::Invalid));
+        assert_eq!(decode(&[0, 0, 0, 0x80]).err(), Some(DecodeError::Invalid));
+        assert!(decode(&[0, 0, 0xFF, 0x7F]).is_ok());
+    }
+
+    #[test]
+    fn tracks_edges() {
+        let actions = &mut actions();
+        let mut state = InputState::default();
+        let mut step = |frame: InputFrame| {
+            state = state.next(frame);
+            actions.update(&state);
+            [Action::Jump, Action::Pause].map(|action| {
+                (
+                    actions.pressed(action),
+                    actions.held(action),
+                    actions.released(action),
+                )
+            })
+        };
+
+        let idle = (false, false, false);
+        let pressed = (true, true, false);
+        let held = (false, true, false);
+        let released = (false, false, true);
+
+        let frames = [
+            (InputFrame::keys(Keys::A | Keys::L), [pressed, idle]),
+            (
+                InputFrame::keys(Keys::A | Keys::L | Keys::R),
+                [held, pressed],
+            ),
+            // Another binding keeps the action held.
+            (InputFrame::touch(10, 170), [held, released]),
+            (InputFrame::touch(10, 150), [released, idle]),
+            (InputFrame::default(), [idle, idle]),
+            (InputFrame::keys(Keys::R | Keys::L), [idle, pressed]),
+        ];
+
+        for (index, (frame, expected)) in frames.into_iter().enumerate() {
+            assert_eq!(step(frame), expected, "frame {index}");
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use super::Touch;
+
+/// Number of frames per second, used to convert swipe velocities.
+const FRAMES_PER_SECOND: u32 = 60;
+
+/// A gesture recognized by [`Gestures`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// The pen was briefly pressed and lifted without moving.
+    Tap { x: u16, y: u16 },
+    /// A second tap close to a previous one.
+    /// The first tap is still reported as a [`Gesture::Tap`].
+    DoubleTap { x: u16, y: u16 },
+    /// The pen rested in place for a while. Lifting it afterwards is no tap.
+    LongPress { x: u16, y: u16 },
+    /// The pen moved while pressed, by `dx` and `dy` since the previous drag or the press.
+    Drag { x: u16, y: u16, dx: i16, dy: i16 },
+    /// The pen was lifted after a drag that was not a swipe.
+    DragEnd { x: u16, y: u16 },
+    /// The pen was quickly moved in a direction and lifted.
+    Swipe {
+        direction: Direction,
+        /// Distance along the direction in pixels.
+        distance: u16,
+        /// Average speed in pixels per second.
+        velocity: u16,
+    },
+}
+
+/// The direction of a [`Gesture::Swipe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Distances in pixels and durations in frames that distinguish gestures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Distance the pen may move before a press turns into a drag.
+    pub tap_distance: u16,
+    /// Maximum duration of a tap.
+    pub tap_frames: u32,
+    /// Maximum time between two taps of a double tap.
+    pub double_tap_frames: u32,
+    /// Maximum distance between two taps of a double tap.
+    pub double_tap_distance: u16,
+    /// Duration after which a resting press becomes a long press.
+    pub long_press_frames: u32,
+    /// Minimum distance of a swipe.
+    pub swipe_distance: u16,
+    /// Maximum duration of a swipe.
+    pub swipe_frames: u32,
+}
+
+impl Default for Thresholds {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            tap_distance: 6,
+            tap_frames: 15,
+            double_tap_frames: 20,
+            double_tap_distance: 12,
+            long_press_frames: 40,
+            swipe_distance: 40,
+            swipe_frames: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    start: (u16, u16),
+    last: (u16, u16),
+    frame: u32,
+    dragging: bool,
+    long_pressed: bool,
+}
+
+/// A recognizer turning a stream of touch readings into [`Gesture`]s.
+///
+/// The recognizer only depends on the readings passed to [`Gestures::update`], once per frame,
+/// so it can be driven by any [`InputSource`](super::InputSource) or a recorded touch stream.
+#[derive(Debug, Default, Clone)]
+pub struct Gestures {
+    thresholds: Thresholds,
+    frame: u32,
+    press: Option<Press>,
+    last_tap: Option<(u32, (u16, u16))>,
+}
+
+impl Gestures {
+    /// Creates a recognizer with the given thresholds.
+    #[inline]
+    pub const fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            frame: 0,
+            press: None,
+            last_tap: None,
+        }
+    }
+
+    /// Returns the thresholds of the recognizer.
+    #[inline]
+    pub fn thresholds(&self) -> &Thresholds {
+        &self.thresholds
+    }
+
+    /// Processes the touch reading of a frame and returns the gesture it completes, if any.
+    pub fn update(&mut self, touch: &Touch) -> Option<Gesture> {
+        let gesture = match touch.down {
+            true => self.hold(touch.position()),
+            false => self.release(),
+        };
+
+        self.frame = self.frame.wrapping_add(1);
+        gesture
+    }
+
+    fn hold(&mut self, position: (u16, u16)) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+        let frame = self.frame;
+        let press = self.press.get_or_insert(Press {
+            start: position,
+            last: position,
+            frame,
+            dragging: false,
+            long_pressed: false,
+        });
+
+        if !press.dragging && distance(press.start, position) > thresholds.tap_distance {
+            // The first drag covers the whole movement since the press.
+            press.dragging = true;
+            press.last = press.start;
+        }
+
+        if press.dragging {
+            let (dx, dy) = delta(press.last, position);
+            press.last = position;
+            return (dx != 0 || dy != 0).then_some(Gesture::Drag {
+                x: position.0,
+                y: position.1,
+                dx,
+                dy,
+            });
+        }
+
+        press.last = position;
+        let held = frame.wrapping_sub(press.frame);
+        if !press.long_pressed && held >= thresholds.long_press_frames {
+            press.long_pressed = true;
+            let (x, y) = press.start;
+            return Some(Gesture::LongPress { x, y });
+        }
+
+        None
+    }
+
+    fn release(&mut self) -> Option<Gesture> {
+        let thresholds = self.thresholds;
+        let press = self.press.take()?;
+        let duration = self.frame.wrapping_sub(press.frame).max(1);
+        let (x, y) = press.last;
+
+        if press.dragging {
+            let (dx, dy) = delta(press.start, press.last);
+            let distance = dx.unsigned_abs().max(dy.unsigned_abs());
+            if duration > thresholds.swipe_frames || distance < thresholds.swipe_distance {
+                return Some(Gesture::DragEnd { x, y });
+            }
+
+            let direction = match dx.unsigned_abs() >= dy.unsigned_abs() {
+                true if dx < 0 => Direction::Left,
+                true => Direction::Right,
+                false if dy < 0 => Direction::Up,
+                false => Direction::Down,
+            };
+            let velocity = distance as u32 * FRAMES_PER_SECOND / duration;
+
+            return Some(Gesture::Swipe {
+                direction,
+                distance,
+                velocity: velocity.min(u16::MAX as u32) as u16,
+            });
+        }
+
+        if press.long_pressed || duration > thresholds.tap_frames {
+            return None;
+        }
+
+        let double = self.last_tap.take().is_some_and(|(frame, position)| {
+            self.frame.wrapping_sub(frame) <= thresholds.double_tap_frames
+                && distance(position, press.start) <= thresholds.double_tap_distance
+        });
+
+        match double {
+            true => Some(Gesture::DoubleTap { x, y }),
+            false => {
+                self.last_tap = Some((self.frame, press.start));
+                Some(Gesture::Tap { x, y })
+            }
+        }
+    }
+}
+
+/// Returns the difference between two positions.
+#[inline]
+fn delta(from: (u16, u16), to: (u16, u16)) -> (i16, i16) {
+    (to.0 as i16 - from.0 as i16, to.1 as i16 - from.1 as i16)
+}
+
+/// Returns the largest distance between two positions along either axis.
+#[inline]
+fn distance(from: (u16, u16), to: (u16, u16)) -> u16 {
+    from.0.abs_diff(to.0).max(from.1.abs_diff(to.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use core::iter::repeat_n;
+
+    /// Feeds one reading per frame, where `None` means the pen is up.
+    fn run(
+        gestures: &mut Gestures,
+        readings: impl IntoIterator<Item = Option<(u16, u16)>>,
+    ) -> Vec<Gesture> {
+        readings
+            .into_iter()
+            .filter_map(|reading| {
+                let (x, y) = reading.unwrap_or_default();
+                let touch = Touch {
+                    x,
+                    y,
+                    down: reading.is_some(),
+                    ..Touch::default()
+                };
+                gestures.update(&touch)
+            })
+            .collect()
+    }
+
+    fn press(x: u16, y: u16, frames: usize) -> impl Iterator<Item = Option<(u16, u16)>> {
+        repeat_n(Some((x, y)), frames)
+    }
+
+    fn up(frames: usize) -> impl Iterator<Item = Option<(u16, u16)>> {
+        repeat_n(None, frames)
+    }
+
+    #[test]
+    fn tap() {
+        let mut gestures = Gestures::default();
+        let events = run(&mut gestures, press(10, 20, 3).chain(up(1)));
+        assert_eq!(events, [Gesture::Tap { x: 10, y: 20 }]);
+    }
+
+    #[test]
+    fn tap_allows_jitter() {
+        let mut gestures = Gestures::default();
+        let readings = [Some((10, 20)), Some((13, 18)), Some((16, 20)), None];
+        assert_eq!(
+            run(&mut gestures, readings),
+            [Gesture::Tap { x: 16, y: 20 }]
+        );
+    }
+
+    #[test]
+    fn slow_press_is_no_tap() {
+        let mut gestures = Gestures::default();
+        assert_eq!(run(&mut gestures, press(10, 20, 20).chain(up(1))), []);
+    }
+
+    #[test]
+    fn double_tap() {
+        let mut gestures = Gestures::default();
+        let readings = press(10, 20, 3)
+            .chain(up(5))
+            .chain(press(14, 24, 3))
+            .chain(up(5))
+            .chain(press(14, 24, 3))
+            .chain(up(1));
+
+        assert_eq!(
+            run(&mut gestures, readings),
+            [
+                Gesture::Tap { x: 10, y: 20 },
+                Gesture::DoubleTap { x: 14, y: 24 },
+                Gesture::Tap { x: 14, y: 24 },
+            ]
+        );
+    }
+
+    #[test]
+    fn distant_or_late_taps_are_no_double_tap() {
+        let mut gestures = Gestures::default();
+        let readings = press(10, 20, 3)
+            .chain(up(5))
+            .chain(press(60, 20, 3))
+            .chain(up(30))
+            .chain(press(60, 20, 3))
+            .chain(up(1));
+
+        assert_eq!(
+            run(&mut gestures, readings),
+            [
+                Gesture::Tap { x: 10, y: 20 },
+                Gesture::Tap { x: 60, y: 20 },
+                Gesture::Tap { x: 60, y: 20 },
+            ]
+        );
+    }
+
+    #[test]
+    fn long_press_then_release() {
+        let mut gestures = Gestures::default();
+        let frames = gestures.thresholds().long_press_frames as usize;
+
+        let events = run(&mut gestures, press(30, 40, frames));
+        assert_eq!(events, []);
+
+        let events = run(&mut gestures, press(30, 40, frames).chain(up(1)));
+        assert_eq!(events, [Gesture::LongPress { x: 30, y: 40 }]);
+    }
+
+    #[test]
+    fn drag_deltas() {
+        let mut gestures = Gestures::default();
+        let readings = [
+            Some((100, 100)),
+            Some((103, 100)),
+            Some((110, 98)),
+            Some((110, 98)),
+            Some((105, 101)),
+        ];
+
+        assert_eq!(
+            run(&mut gestures, readings),
+            [
+                Gesture::Drag {
+                    x: 110,
+                    y: 98,
+                    dx: 10,
+                    dy: -2
+                },
+                Gesture::Drag {
+                    x: 105,
+                    y: 101,
+                    dx: -5,
+                    dy: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn drag_deltas_add_up() {
+        let mut gestures = Gestures::default();
+        let readings = [(30, 60), (33, 58), (35, 57), (45, 57), (44, 70), (20, 90)];
+
+        let (dx, dy) =
+            run(&mut gestures, readings.map(Some))
+                .iter()
+                .fold((0, 0), |(x, y), gesture| match *gesture {
+                    Gesture::Drag { dx, dy, .. } => (x + dx, y + dy),
+                    _ => (x, y),
+                });
+        assert_eq!((dx, dy), (20 - 30, 90 - 60));
+    }
+
+    #[test]
+    fn drag_end() {
+        let mut gestures = Gestures::default();
+        let readings = [Some((100, 100)), Some((120, 100)), None];
+        assert_eq!(
+            run(&mut gestures, readings),
+            [
+                Gesture::Drag {
+                    x: 120,
+                    y: 100,
+                    dx: 20,
+                    dy: 0
+                },
+                Gesture::DragEnd { x: 120, y: 100 },
+            ]
+        );
+
+        // Far but slow movements are no swipe either.
+        let mut gestures = Gestures::default();
+        let readings = press(100, 100, 1).chain(press(200, 100, 30)).chain(up(1));
+        let events = run(&mut gestures, readings);
+        assert_eq!(events.last(), Some(&Gesture::DragEnd { x: 200, y: 100 }));
+    }
+
+    #[test]
+    fn swipe() {
+        let mut gestures = Gestures::default();
+        let readings = [
+            Some((50, 100)),
+            Some((70, 100)),
+            Some((90, 102)),
+            Some((110, 104)),
+            None,
+        ];
+
+        assert_eq!(
+            run(&mut gestures, readings).last(),
+            Some(&Gesture::Swipe {
+                direction: Direction::Right,
+                distance: 60,
+                velocity: 60 * FRAMES_PER_SECOND as u16 / 4,
+            })
+        );
+    }
+
+    #[test]
+    fn swipe_directions() {
+        let swipe = |to: (u16, u16)| {
+            let mut gestures = Gestures::default();
+            let readings = [Some((100, 100)), Some(to), None];
+            match run(&mut gestures, readings).last() {
+                Some(&Gesture::Swipe { direction, .. }) => Some(direction),
+                _ => None,
+            }
+        };
+
+        assert_eq!(swipe((40, 110)), Some(Direction::Left));
+        assert_eq!(swipe((160, 90)), Some(Direction::Right));
+        assert_eq!(swipe((90, 40)), Some(Direction::Up));
+        assert_eq!(swipe((110, 160)), Some(Direction::Down));
+    }
+}
@@ -0,0 +1,112 @@
+use super::Keys;
+use crate::interrupt::{self, Interrupt};
+
+/// Keypad input register `KEYINPUT`, with a cleared bit for every held key.
+const KEYINPUT: *const u16 = 0x0400_0130 as _;
+/// Keypad interrupt control register `KEYCNT`.
+const KEYCNT: *mut u16 = 0x0400_0132 as _;
+
+/// Keys that can raise the keypad interrupt.
+const MASK: u32 = 0x03FF;
+/// Enables the keypad interrupt.
+const IRQ_ENABLE: u16 = 1 << 14;
+/// Raises the interrupt only when all selected keys are held, instead of any of them.
+const AND_MODE: u16 = 1 << 15;
+
+/// The condition on the selected keys that raises the keypad interrupt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCondition {
+    /// Any of the keys is held.
+    Any,
+    /// All of the keys are held at the same time.
+    All,
+}
+
+/// Configuration of the keypad interrupt [`Interrupt::KEYS`].
+///
+/// Only `A`, `B`, `SELECT`, `START`, the D-pad, `R` and `L` can raise the interrupt.
+/// The other keys are read by the ARM7 and are not connected to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyInterrupt {
+    keys: Keys,
+    condition: KeyCondition,
+}
+
+impl KeyInterrupt {
+    /// Raises the interrupt when any of the keys is held.
+    #[inline]
+    pub const fn any(keys: Keys) -> Self {
+        Self::new(keys, KeyCondition::Any)
+    }
+
+    /// Raises the interrupt when all of the keys are held at the same time.
+    #[inline]
+    pub const fn all(keys: Keys) -> Self {
+        Self::new(keys, KeyCondition::All)
+    }
+
+    /// Raises the interrupt when the keys are held according to the condition.
+    #[inline]
+    pub const fn new(keys: Keys, condition: KeyCondition) -> Self {
+        assert!(
+            keys.0 & !MASK == 0,
+            "Only A, B, SELECT, START, the D-pad, R and L can raise the keypad interrupt"
+        );
+        Self { keys, condition }
+    }
+
+    /// Returns the keys that raise the interrupt.
+    #[inline]
+    pub const fn keys(&self) -> Keys {
+        self.keys
+    }
+
+    /// Returns the condition on the keys that raises the interrupt.
+    #[inline]
+    pub const fn condition(&self) -> KeyCondition {
+        self.condition
+    }
+
+    /// Configures and enables the keypad interrupt.
+    #[inline]
+    pub fn enable(self) {
+        let mode = match self.condition {
+            KeyCondition::Any => 0,
+            KeyCondition::All => AND_MODE,
+        };
+
+        // SAFETY: `KEYCNT` is a valid hardware register.
+        unsafe { KEYCNT.write_volatile(IRQ_ENABLE | mode | self.keys.0 as u16) };
+        interrupt::enable(Interrupt::KEYS);
+    }
+
+    /// Disables the keypad interrupt.
+    #[inline]
+    pub fn disable() {
+        interrupt::disable(Interrupt::KEYS);
+        // SAFETY: `KEYCNT` is a valid hardware register.
+        unsafe { KEYCNT.write_volatile(0) };
+    }
+
+    /// Halts the CPU until the keys are held according to the condition.
+    ///
+    /// Returns immediately if they are already held.
+    /// The keypad interrupt is disabled again afterwards.
+    pub fn wait(self) {
+        self.enable();
+
+        // SAFETY: Reading the keypad input register has no side effects.
+        let pressed = !unsafe { KEYINPUT.read_volatile() } as u32 & self.keys.0;
+        let satisfied = match self.condition {
+            KeyCondition::Any => pressed != 0,
+            KeyCondition::All => pressed == self.keys.0,
+        };
+
+        if !satisfied {
+            // SAFETY: The keypad interrupt is enabled, so the wait ends.
+            unsafe { nds_sys::swiIntrWait(1, Interrupt::KEYS.bits()) };
+        }
+
+        Self::disable();
+    }
+}
@@ -0,0 +1,135 @@
+use super::InputState;
+use crate::system;
+use portable_atomic::{AtomicUsize, Ordering};
+
+/// Number of [`Lid`]s that took over sleeping from libnds.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// A change of the lid state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LidEvent {
+    Closed,
+    Opened,
+}
+
+/// Tracks the lid and optionally puts the system to sleep while it is closed.
+///
+/// From its first update on, a lid takes over sleeping from libnds, which otherwise sleeps on its
+/// own when the lid is closed. Sleeping on lid close is handed back to libnds once every such
+/// lid is dropped.
+///
+/// # Examples
+/// ```ignore
+/// use nds::sys::input::{HardwareInput, InputSource, Lid};
+///
+/// let mut input = HardwareInput;
+/// let mut lid = Lid::new().auto_sleep(true).on_sleep(pause).on_wake(resume);
+///
+/// loop {
+///     let state = input.poll();
+///     lid.update(&state);
+/// }
+/// # fn pause() {}
+/// # fn resume() {}
+/// ```
+#[derive(Debug, Default)]
+pub struct Lid {
+    closed: bool,
+    auto_sleep: bool,
+    before_sleep: Option<fn()>,
+    after_wake: Option<fn()>,
+    active: bool,
+}
+
+impl Lid {
+    /// Creates a tracker for an open lid, without auto sleep.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            closed: false,
+            auto_sleep: false,
+            before_sleep: None,
+            after_wake: None,
+            active: false,
+        }
+    }
+
+    /// Sets whether the system is put to sleep when the lid is closed.
+    #[inline]
+    pub const fn auto_sleep(mut self, auto_sleep: bool) -> Self {
+        self.auto_sleep = auto_sleep;
+        self
+    }
+
+    /// Sets a callback invoked right before sleeping, such as to pause timers and audio.
+    #[inline]
+    pub const fn on_sleep(mut self, callback: fn()) -> Self {
+        self.before_sleep = Some(callback);
+        self
+    }
+
+    /// Sets a callback invoked right after waking up.
+    #[inline]
+    pub const fn on_wake(mut self, callback: fn()) -> Self {
+        self.after_wake = Some(callback);
+        self
+    }
+
+    /// Returns whether the lid was closed at the last update.
+    #[inline]
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Updates the lid from the input of a new frame and returns its change, if any.
+    ///
+    /// With auto sleep, closing the lid sleeps until it is opened again before returning
+    /// [`LidEvent::Closed`], and [`LidEvent::Opened`] follows with the next update.
+    pub fn update(&mut self, state: &InputState) -> Option<LidEvent> {
+        if !self.active {
+            self.active = true;
+            if ACTIVE.fetch_add(1, Ordering::Relaxed) == 0 {
+                // SAFETY: Only stops libnds from sleeping on its own when the lid is closed.
+                unsafe { nds_sys::disableSleep() };
+            }
+        }
+
+        let closed = state.lid_closed();
+        if closed == self.closed {
+            return None;
+        }
+
+        self.closed = closed;
+        match closed {
+            true => {
+                if self.auto_sleep {
+                    self.sleep();
+                }
+                Some(LidEvent::Closed)
+            }
+            false => Some(LidEvent::Opened),
+        }
+    }
+
+    /// Invokes the sleep callback, sleeps until the lid is opened and invokes the wake callback.
+    pub fn sleep(&self) {
+        if let Some(callback) = self.before_sleep {
+            callback();
+        }
+
+        system::sleep();
+
+        if let Some(callback) = self.after_wake {
+            callback();
+        }
+    }
+}
+
+impl Drop for Lid {
+    fn drop(&mut self) {
+        if self.active && ACTIVE.fetch_sub(1, Ordering::Relaxed) == 1 {
+            // SAFETY: Hands sleeping on lid close back to libnds.
+            unsafe { nds_sys::enableSleep() };
+        }
+    }
+}
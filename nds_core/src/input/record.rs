@@ -0,0 +1,383 @@
+//! Recording of input for deterministic replay.
+//!
+//! A [`Recording`] is serialized as the magic bytes `NDSI` and a format version byte, followed
+//! by runs of identical frames. Each run starts with the held keys as a little endian `u16`,
+//! with bit 15 set if the pen touches the screen. In that case the touch position follows as
+//! one byte each for `x` and `y`. The run ends with the number of frames as an unsigned LEB128,
+//! which is at least one and fits into 32 bits.
+
+use super::{InputFrame, InputSource, InputState, Keys, ScriptedInput};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::slice::Iter;
+
+/// Magic bytes at the start of a serialized recording.
+const MAGIC: &[u8; 4] = b"NDSI";
+/// Version of the serialization format.
+const VERSION: u8 = 1;
+/// Flag in the key bits of a run marking a touch position.
+const TOUCH_FLAG: u16 = 1 << 15;
+
+/// An error decoding a serialized [`Recording`] or [`ActionMap`](super::ActionMap).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data does not start with the expected magic bytes.
+    Magic,
+    /// The data was written by an unsupported version of the format.
+    Version(u8),
+    /// The data ends in the middle of an entry.
+    Truncated,
+    /// The data contains an invalid value.
+    Invalid,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Magic => f.write_str("Unrecognized data"),
+            Self::Version(version) => write!(f, "Unsupported format version {version}"),
+            Self::Truncated => f.write_str("Truncated data"),
+            Self::Invalid => f.write_str("Invalid data"),
+        }
+    }
+}
+
+/// A sequence of input frames, stored as runs of identical frames.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Recording {
+    runs: Vec<(InputFrame, u32)>,
+}
+
+impl Recording {
+    /// Creates an empty recording.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { runs: Vec::new() }
+    }
+
+    /// Appends a frame.
+    pub fn push(&mut self, frame: InputFrame) {
+        match self.runs.last_mut() {
+            Some((last, count)) if *last == frame && *count < u32::MAX => *count += 1,
+            _ => self.runs.push((frame, 1)),
+        }
+    }
+
+    /// Returns the number of recorded frames.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(|&(_, count)| count as usize).sum()
+    }
+
+    /// Returns whether no frames are recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Returns an iterator over all recorded frames.
+    #[inline]
+    pub fn frames(&self) -> Frames<'_> {
+        Frames {
+            runs: self.runs.iter(),
+            run: None,
+        }
+    }
+
+    /// Returns an input source replaying the recorded frames.
+    #[inline]
+    pub fn replay(&self) -> ScriptedInput<Frames<'_>> {
+        ScriptedInput::new(self.frames())
+    }
+
+    /// Serializes the recording.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 1 + self.runs.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+
+        for &(frame, count) in &self.runs {
+            let keys = (frame.keys & !Keys::TOUCH).0 as u16;
+            match frame.touch {
+                Some((x, y)) => {
+                    bytes.extend_from_slice(&(keys | TOUCH_FLAG).to_le_bytes());
+                    bytes.extend_from_slice(&[x.min(255) as u8, y.min(255) as u8]);
+                }
+                None => bytes.extend_from_slice(&keys.to_le_bytes()),
+            }
+
+            let mut count = count;
+            while count >= 0x80 {
+                bytes.push(count as u8 | 0x80);
+                count >>= 7;
+            }
+            bytes.push(count as u8);
+        }
+
+        bytes
+    }
+
+    /// Deserializes a recording written by [`Recording::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let bytes = bytes.strip_prefix(MAGIC).ok_or(DecodeError::Magic)?;
+        let (&version, mut bytes) = bytes.split_first().ok_or(DecodeError::Truncated)?;
+        if version != VERSION {
+            return Err(DecodeError::Version(version));
+        }
+
+        let mut runs = Vec::new();
+        while !bytes.is_empty() {
+            let mut take = |len: usize| match bytes.split_at_checked(len) {
+                Some((head, tail)) => {
+                    bytes = tail;
+                    Ok(head)
+                }
+                None => Err(DecodeError::Truncated),
+            };
+
+            let keys = take(2)?;
+            let keys = u16::from_le_bytes([keys[0], keys[1]]);
+            let touch = match keys & TOUCH_FLAG != 0 {
+                true => take(2).map(|touch| Some((touch[0] as u16, touch[1] as u16)))?,
+                false => None,
+            };
+
+            let mut count = 0u32;
+            for shift in (0..32).step_by(7) {
+                let byte = take(1)?[0];
+                // The fifth byte only holds the top four bits and has to end the number.
+                if shift == 28 && byte > 0x0F {
+                    return Err(DecodeError::Invalid);
+                }
+
+                count |= ((byte & 0x7F) as u32) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+
+            if count == 0 {
+                return Err(DecodeError::Invalid);
+            }
+
+            let keys = Keys((keys & !TOUCH_FLAG) as _);
+            runs.push((InputFrame { keys, touch }, count));
+        }
+
+        Ok(Self { runs })
+    }
+}
+
+/// An iterator over the frames of a [`Recording`].
+#[derive(Debug, Clone)]
+pub struct Frames<'r> {
+    runs: Iter<'r, (InputFrame, u32)>,
+    run: Option<(InputFrame, u32)>,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = InputFrame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match &mut self.run {
+                Some((frame, count)) if *count > 0 => {
+                    *count -= 1;
+                    return Some(*frame);
+                }
+                _ => self.run = Some(*self.runs.next()?),
+            }
+        }
+    }
+}
+
+/// An input source that records the input of another source while passing it through.
+#[derive(Debug, Clone)]
+pub struct Recorder<S> {
+    source: S,
+    recording: Recording,
+}
+
+impl<S: InputSource> Recorder<S> {
+    /// Starts recording the input of `source`.
+    #[inline]
+    pub const fn new(source: S) -> Self {
+        Self {
+            source,
+            recording: Recording::new(),
+        }
+    }
+
+    /// Returns the input recorded so far.
+    #[inline]
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// Stops recording and returns the recorded input.
+    #[inline]
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+impl<S: InputSource> InputSource for Recorder<S> {
+    fn poll(&mut self) -> InputState {
+        let state = self.source.poll();
+        self.recording.push(state.frame());
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const HEADER: &[u8] = b"NDSI\x01";
+
+    fn recording(runs: &[(InputFrame, u32)]) -> Recording {
+        Recording {
+            runs: runs.to_vec(),
+        }
+    }
+
+    fn encoded(run: &[u8]) -> Vec<u8> {
+        [HEADER, run].concat()
+    }
+
+    #[test]
+    fn push_merges_runs() {
+        let mut recording = Recording::new();
+        assert!(recording.is_empty());
+
+        recording.push(InputFrame::keys(Keys::A));
+        recording.push(InputFrame::keys(Keys::A));
+        recording.push(InputFrame::touch(1, 2));
+
+        assert_eq!(recording.len(), 3);
+        assert_eq!(
+            recording,
+            self::recording(&[(InputFrame::keys(Keys::A), 2), (InputFrame::touch(1, 2), 1)])
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let recording = recording(&[
+            (InputFrame::default(), 1),
+            (InputFrame::keys(Keys::A | Keys::L), 127),
+            (InputFrame::keys(Keys::all() & !Keys::TOUCH), 128),
+            (
+                InputFrame {
+                    keys: Keys::B,
+                    touch: Some((255, 191)),
+                },
+                300,
+            ),
+            (InputFrame::touch(0, 0), 16_384),
+            (InputFrame::keys(Keys::DEBUG), u32::MAX),
+        ]);
+
+        let bytes = recording.to_bytes();
+        assert_eq!(Recording::from_bytes(&bytes), Ok(recording));
+    }
+
+    #[test]
+    fn round_trip_frames() {
+        let frames = [
+            InputFrame::keys(Keys::START),
+            InputFrame::touch(10, 20),
+            InputFrame::touch(10, 20),
+            InputFrame::default(),
+        ];
+
+        let mut recorder = Recorder::new(ScriptedInput::new(frames));
+        for _ in 0..frames.len() {
+            recorder.poll();
+        }
+
+        let bytes = recorder.finish().to_bytes();
+        let recording = Recording::from_bytes(&bytes).unwrap();
+        assert!(recording.frames().eq(frames));
+    }
+
+    #[test]
+    fn encodes_runs() {
+        let bytes = recording(&[(InputFrame::keys(Keys::A), 1)]).to_bytes();
+        assert_eq!(bytes, encoded(&[0x01, 0x00, 0x01]));
+
+        let bytes = recording(&[(InputFrame::touch(300, 7), 2)]).to_bytes();
+        assert_eq!(bytes, encoded(&[0x00, 0x80, 255, 7, 0x02]));
+    }
+
+    #[test]
+    fn encodes_long_runs() {
+        let run = |count| recording(&[(InputFrame::default(), count)]).to_bytes();
+
+        assert_eq!(run(127), encoded(&[0, 0, 0x7F]));
+        assert_eq!(run(128), encoded(&[0, 0, 0x80, 0x01]));
+        assert_eq!(run(300), encoded(&[0, 0, 0xAC, 0x02]));
+        assert_eq!(
+            run(u32::MAX),
+            encoded(&[0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F])
+        );
+    }
+
+    #[test]
+    fn rejects_header() {
+        assert_eq!(Recording::from_bytes(b""), Err(DecodeError::Magic));
+        assert_eq!(Recording::from_bytes(b"NDSA\x01"), Err(DecodeError::Magic));
+        assert_eq!(Recording::from_bytes(b"NDSI"), Err(DecodeError::Truncated));
+        assert_eq!(
+            Recording::from_bytes(b"NDSI\x02"),
+            Err(DecodeError::Version(2))
+        );
+        assert_eq!(Recording::from_bytes(HEADER), Ok(Recording::new()));
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        let bytes = recording(&[
+            (InputFrame::touch(1, 2), 1000),
+            (InputFrame::keys(Keys::A), 1),
+        ])
+        .to_bytes();
+
+        // The first run ends after the keys, the touch position and two bytes of count.
+        let boundary = HEADER.len() + 6;
+        for len in (HEADER.len() + 1..bytes.len()).filter(|&len| len != boundary) {
+            assert_eq!(
+                Recording::from_bytes(&bytes[..len]),
+                Err(DecodeError::Truncated),
+                "{len} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_overlong_count() {
+        let bytes = encoded(&[0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0x1F]);
+        assert_eq!(Recording::from_bytes(&bytes), Err(DecodeError::Invalid));
+
+        let bytes = encoded(&[0, 0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]);
+        assert_eq!(Recording::from_bytes(&bytes), Err(DecodeError::Invalid));
+    }
+
+    #[test]
+    fn rejects_empty_run() {
+        let bytes = encoded(&[0, 0, 0]);
+        assert_eq!(Recording::from_bytes(&bytes), Err(DecodeError::Invalid));
+    }
+
+    #[test]
+    fn ignores_touch_key() {
+        let frame = InputFrame {
+            keys: Keys::TOUCH | Keys::A,
+            touch: None,
+        };
+        let bytes = recording(&[(frame, 1)]).to_bytes();
+        let frames: Vec<_> = Recording::from_bytes(&bytes).unwrap().frames().collect();
+        assert_eq!(frames, vec![InputFrame::keys(Keys::A)]);
+    }
+}
@@ -0,0 +1,256 @@
+use super::{Keys, Touch};
+
+/// A snapshot of all input of a single frame.
+///
+/// Unlike [`Keys::down`] and [`Keys::held`], a snapshot does not depend on global state,
+/// so input handling written against it can be driven by any [`InputSource`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputState {
+    /// Keys held this frame.
+    pub held: Keys,
+    /// Keys pressed this frame.
+    pub down: Keys,
+    /// Keys released this frame.
+    pub up: Keys,
+    /// The touchscreen reading of this frame.
+    pub touch: Touch,
+}
+
+impl InputState {
+    /// Returns the state following this one, with the edges derived from the difference.
+    ///
+    /// The touch position is kept when the pen is lifted, so it can be used for the release.
+    pub fn next(&self, frame: InputFrame) -> Self {
+        let mut held = frame.keys;
+        held.set(Keys::TOUCH, frame.touch.is_some());
+
+        let was_down = self.touch.down;
+        let (x, y) = frame.touch.unwrap_or(self.touch.position());
+        let touch = Touch {
+            x,
+            y,
+            down: frame.touch.is_some(),
+            pressed: frame.touch.is_some() && !was_down,
+            released: frame.touch.is_none() && was_down,
+            ..Touch::default()
+        };
+
+        Self {
+            held,
+            down: held & !self.held,
+            up: self.held & !held,
+            touch,
+        }
+    }
+
+    /// Returns whether the lid is closed.
+    #[inline]
+    pub const fn lid_closed(&self) -> bool {
+        self.held.intersects(Keys::LID)
+    }
+
+    /// Returns the input of this frame without the edges.
+    #[inline]
+    pub fn frame(&self) -> InputFrame {
+        InputFrame {
+            keys: self.held & !Keys::TOUCH,
+            touch: self.touch.down.then_some(self.touch.position()),
+        }
+    }
+}
+
+/// The raw input of a single frame, from which an [`InputState`] is derived.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InputFrame {
+    /// Keys held this frame.
+    pub keys: Keys,
+    /// The touch position, if the pen touches the screen.
+    pub touch: Option<(u16, u16)>,
+}
+
+impl InputFrame {
+    /// Creates a frame with the given keys held and the pen up.
+    #[inline]
+    pub const fn keys(keys: Keys) -> Self {
+        Self { keys, touch: None }
+    }
+
+    /// Creates a frame with no keys held and the pen touching the given position.
+    #[inline]
+    pub const fn touch(x: u16, y: u16) -> Self {
+        Self {
+            keys: Keys::empty(),
+            touch: Some((x, y)),
+        }
+    }
+}
+
+/// A source of input producing one [`InputState`] per frame.
+pub trait InputSource {
+    /// Produces the input of the next frame.
+    ///
+    /// Call this exactly once per frame.
+    fn poll(&mut self) -> InputState;
+}
+
+/// Input read from the keypad and touchscreen.
+#[cfg(feature = "sys")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HardwareInput;
+
+#[cfg(feature = "sys")]
+impl InputSource for HardwareInput {
+    /// Scans the keypad and reads the touchscreen.
+    fn poll(&mut self) -> InputState {
+        Keys::scan();
+
+        InputState {
+            held: Keys::held(),
+            down: Keys::down(),
+            up: Keys::up(),
+            touch: Touch::read(),
+        }
+    }
+}
+
+/// Input played back from a sequence of frames, such as a script in a test.
+///
+/// Once the frames run out, no input is reported anymore.
+///
+/// # Examples
+/// ```
+/// use nds_core::input::{InputFrame, InputSource, Keys, ScriptedInput};
+///
+/// let mut input = ScriptedInput::new([InputFrame::keys(Keys::A), InputFrame::default()]);
+/// assert!(input.poll().down.intersects(Keys::A));
+/// assert!(input.poll().up.intersects(Keys::A));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScriptedInput<I> {
+    frames: I,
+    state: InputState,
+}
+
+impl<I: Iterator<Item = InputFrame>> ScriptedInput<I> {
+    /// Creates a source playing back the given frames.
+    #[inline]
+    pub fn new(frames: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            frames: frames.into_iter(),
+            state: InputState::default(),
+        }
+    }
+
+    /// Returns the state produced by the last poll.
+    #[inline]
+    pub fn state(&self) -> InputState {
+        self.state
+    }
+}
+
+impl<I: Iterator<Item = InputFrame>> InputSource for ScriptedInput<I> {
+    fn poll(&mut self) -> InputState {
+        let frame = self.frames.next().unwrap_or_default();
+        self.state = self.state.next(frame);
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(frames: &[InputFrame]) -> InputState {
+        frames
+            .iter()
+            .fold(InputState::default(), |state, &frame| state.next(frame))
+    }
+
+    #[test]
+    fn key_edges() {
+        let state = run(&[InputFrame::keys(Keys::A)]);
+        assert_eq!(
+            (state.held, state.down, state.up),
+            (Keys::A, Keys::A, Keys::empty())
+        );
+
+        let state = state.next(InputFrame::keys(Keys::A | Keys::B));
+        assert_eq!(state.held, Keys::A | Keys::B);
+        assert_eq!((state.down, state.up), (Keys::B, Keys::empty()));
+
+        let state = state.next(InputFrame::keys(Keys::B));
+        assert_eq!((state.down, state.up), (Keys::empty(), Keys::A));
+
+        let state = state.next(InputFrame::keys(Keys::B));
+        assert_eq!((state.down, state.up), (Keys::empty(), Keys::empty()));
+
+        let state = state.next(InputFrame::default());
+        assert_eq!((state.held, state.up), (Keys::empty(), Keys::B));
+    }
+
+    #[test]
+    fn simultaneous_press_and_release() {
+        let state = run(&[InputFrame::keys(Keys::L), InputFrame::keys(Keys::R)]);
+        assert_eq!((state.down, state.up), (Keys::R, Keys::L));
+    }
+
+    #[test]
+    fn touch_edges() {
+        let state = run(&[InputFrame::touch(10, 20)]);
+        assert!(state.touch.down && state.touch.pressed && !state.touch.released);
+        assert_eq!(state.touch.position(), (10, 20));
+        assert!(state.held.intersects(Keys::TOUCH) && state.down.intersects(Keys::TOUCH));
+
+        let state = state.next(InputFrame::touch(12, 22));
+        assert!(state.touch.down && !state.touch.pressed && !state.touch.released);
+        assert_eq!(state.touch.position(), (12, 22));
+        assert!(!state.down.intersects(Keys::TOUCH));
+
+        let state = state.next(InputFrame::default());
+        assert!(!state.touch.down && !state.touch.pressed && state.touch.released);
+        assert!(state.up.intersects(Keys::TOUCH) && !state.held.intersects(Keys::TOUCH));
+
+        let state = state.next(InputFrame::default());
+        assert!(!state.touch.released);
+    }
+
+    #[test]
+    fn release_keeps_position() {
+        let state = run(&[InputFrame::touch(30, 40), InputFrame::default()]);
+        assert_eq!(state.touch.position(), (30, 40));
+
+        let state = state.next(InputFrame::default());
+        assert_eq!(state.touch.position(), (30, 40));
+    }
+
+    #[test]
+    fn lid_closed() {
+        assert!(!run(&[InputFrame::keys(Keys::A)]).lid_closed());
+
+        let state = run(&[InputFrame::keys(Keys::LID | Keys::A)]);
+        assert!(state.lid_closed());
+        assert!(!state.next(InputFrame::default()).lid_closed());
+    }
+
+    #[test]
+    fn frame_round_trip() {
+        let frame = InputFrame {
+            keys: Keys::START,
+            touch: Some((1, 2)),
+        };
+        assert_eq!(run(&[frame]).frame(), frame);
+        assert_eq!(
+            run(&[frame, InputFrame::default()]).frame(),
+            InputFrame::default()
+        );
+    }
+
+    #[test]
+    fn scripted_input_runs_out() {
+        let mut input = ScriptedInput::new([InputFrame::keys(Keys::X)]);
+        assert_eq!(input.poll().down, Keys::X);
+        assert_eq!(input.poll().up, Keys::X);
+        assert_eq!(input.poll(), input.state());
+        assert!(input.state().held.is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+#[cfg(feature = "sys")]
+use super::Keys;
+
+/// A single reading of the touchscreen.
+///
+/// Coordinates are only meaningful while the pen is down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Touch {
+    /// Calibrated horizontal position in pixels.
+    pub x: u16,
+    /// Calibrated vertical position in pixels.
+    pub y: u16,
+    /// Raw horizontal ADC value.
+    pub raw_x: u16,
+    /// Raw vertical ADC value.
+    pub raw_y: u16,
+    /// Raw first pressure ADC value.
+    pub z1: u16,
+    /// Raw second pressure ADC value.
+    pub z2: u16,
+    /// Whether the pen touches the screen.
+    pub down: bool,
+    /// Whether the pen touched the screen this frame.
+    pub pressed: bool,
+    /// Whether the pen left the screen this frame.
+    pub released: bool,
+}
+
+impl Touch {
+    /// Reads the touchscreen.
+    ///
+    /// The pen state is taken from the keypad state, so call this after [`Keys::scan`].
+    #[cfg(feature = "sys")]
+    pub fn read() -> Self {
+        let mut raw = nds_sys::touchPosition::default();
+        // SAFETY: `raw` is valid for writes.
+        unsafe { nds_sys::touchRead(&mut raw) };
+
+        let touch = Keys::TOUCH.0;
+        // SAFETY: Reading the keypad state has no side effects.
+        let (held, down, up) =
+            unsafe { (nds_sys::keysHeld(), nds_sys::keysDown(), nds_sys::keysUp()) };
+
+        Self {
+            x: raw.px,
+            y: raw.py,
+            raw_x: raw.rawx,
+            raw_y: raw.rawy,
+            z1: raw.z1,
+            z2: raw.z2,
+            down: held & touch != 0,
+            pressed: down & touch != 0,
+            released: up & touch != 0,
+        }
+    }
+
+    /// Returns the calibrated position in pixels.
+    #[inline]
+    pub const fn position(&self) -> (u16, u16) {
+        (self.x, self.y)
+    }
+
+    /// Estimates the pen pressure between 0 and 255, where larger values mean a firmer press.
+    ///
+    /// The estimate is derived from the resistance between the touch plates, which differs
+    /// between devices, so it is best used for relative comparisons. It is 0 while the pen is up.
+    pub fn pressure(&self) -> u8 {
+        if !self.down || self.z1 == 0 || self.z2 <= self.z1 {
+            return 0;
+        }
+
+        let (x, z1, z2) = (self.raw_x as u32, self.z1 as u32, self.z2 as u32);
+        let resistance = (x * (z2 - z1) / z1) >> 6;
+        (255 * 64 / (64 + resistance)) as u8
+    }
+}
+
+/// Smooths touch readings and suppresses jitter of a resting pen.
+///
+/// Readings are smoothed with an exponential moving average, and movements within the
+/// dejitter distance of the last reported position are ignored. When the pen is lifted,
+/// the last position is kept so it can be used for the release.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TouchFilter {
+    smoothing: u8,
+    dejitter: u16,
+    // Filtered position in 24.8 fixed point.
+    x: i32,
+    y: i32,
+    last: Touch,
+}
+
+impl TouchFilter {
+    /// Creates a filter.
+    ///
+    /// `smoothing` is the weight of the previous position out of 256, where 0 disables smoothing,
+    /// and `dejitter` is the distance in pixels a resting pen has to move to be reported.
+    #[inline]
+    pub const fn new(smoothing: u8, dejitter: u16) -> Self {
+        Self {
+            smoothing,
+            dejitter,
+            x: 0,
+            y: 0,
+            last: Touch {
+                x: 0,
+                y: 0,
+                raw_x: 0,
+                raw_y: 0,
+                z1: 0,
+                z2: 0,
+                down: false,
+                pressed: false,
+                released: false,
+            },
+        }
+    }
+
+    /// Filters a reading.
+    pub fn apply(&mut self, touch: Touch) -> Touch {
+        if !touch.down {
+            self.last = Touch {
+                down: false,
+                pressed: false,
+                released: touch.released,
+                ..self.last
+            };
+            return self.last;
+        }
+
+        let (x, y) = ((touch.x as i32) << 8, (touch.y as i32) << 8);
+        match touch.pressed || !self.last.down {
+            true => (self.x, self.y) = (x, y),
+            false => {
+                let weight = self.smoothing as i32;
+                self.x = (self.x * weight + x * (256 - weight)) >> 8;
+                self.y = (self.y * weight + y * (256 - weight)) >> 8;
+            }
+        }
+
+        let (x, y) = (((self.x + 128) >> 8) as u16, ((self.y + 128) >> 8) as u16);
+        let moved = x.abs_diff(self.last.x).max(y.abs_diff(self.last.y)) > self.dejitter;
+        let position = match touch.pressed || !self.last.down || moved {
+            true => (x, y),
+            false => (self.last.x, self.last.y),
+        };
+
+        self.last = Touch {
+            x: position.0,
+            y: position.1,
+            ..touch
+        };
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn down(x: u16, y: u16) -> Touch {
+        Touch {
+            x,
+            y,
+            down: true,
+            ..Touch::default()
+        }
+    }
+
+    fn pressed(x: u16, y: u16) -> Touch {
+        Touch {
+            pressed: true,
+            ..down(x, y)
+        }
+    }
+
+    fn released() -> Touch {
+        Touch {
+            released: true,
+            ..Touch::default()
+        }
+    }
+
+    /// Filters a stream of readings and returns the reported positions.
+    fn positions(filter: &mut TouchFilter, readings: &[Touch]) -> Vec<(u16, u16)> {
+        readings
+            .iter()
+            .map(|&touch| filter.apply(touch).position())
+            .collect()
+    }
+
+    #[test]
+    fn passes_through_without_filtering() {
+        let mut filter = TouchFilter::new(0, 0);
+        let readings = [pressed(10, 20), down(11, 20), down(40, 3)];
+        assert_eq!(
+            positions(&mut filter, &readings),
+            [(10, 20), (11, 20), (40, 3)]
+        );
+    }
+
+    #[test]
+    fn smooths_movements() {
+        let mut filter = TouchFilter::new(128, 0);
+        let readings = [
+            pressed(0, 100),
+            down(100, 100),
+            down(100, 100),
+            down(100, 0),
+        ];
+        assert_eq!(
+            positions(&mut filter, &readings),
+            [(0, 100), (50, 100), (75, 100), (88, 50)]
+        );
+    }
+
+    #[test]
+    fn suppresses_jitter() {
+        let mut filter = TouchFilter::new(0, 2);
+        let readings = [
+            pressed(50, 50),
+            down(52, 51),
+            down(49, 48),
+            down(53, 50),
+            down(54, 50),
+        ];
+        assert_eq!(
+            positions(&mut filter, &readings),
+            [(50, 50), (50, 50), (50, 50), (53, 50), (53, 50)]
+        );
+    }
+
+    #[test]
+    fn keeps_position_on_release() {
+        let mut filter = TouchFilter::new(128, 0);
+        filter.apply(pressed(20, 30));
+        filter.apply(down(40, 30));
+
+        let touch = filter.apply(released());
+        assert_eq!(touch.position(), (30, 30));
+        assert!(!touch.down && touch.released);
+
+        let touch = filter.apply(Touch::default());
+        assert_eq!(touch.position(), (30, 30));
+        assert!(!touch.down && !touch.released);
+    }
+
+    #[test]
+    fn restarts_on_press() {
+        let mut filter = TouchFilter::new(200, 5);
+        let readings = [pressed(0, 0), down(100, 100), released(), pressed(200, 150)];
+        assert_eq!(
+            positions(&mut filter, &readings)[3],
+            (200, 150),
+            "A new press is neither smoothed nor dejittered"
+        );
+
+        // A press missed between readings restarts the filter too.
+        let mut filter = TouchFilter::new(200, 5);
+        let readings = [pressed(0, 0), released(), down(100, 100)];
+        assert_eq!(positions(&mut filter, &readings)[2], (100, 100));
+    }
+
+    #[test]
+    fn pressure() {
+        let touch = |z1, z2| Touch {
+            raw_x: 2048,
+            z1,
+            z2,
+            ..down(0, 0)
+        };
+
+        assert_eq!(touch(500, 1000).pressure(), 170);
+        assert_eq!(touch(500, 600).pressure(), 233);
+        assert!(touch(500, 600).pressure() > touch(500, 1000).pressure());
+
+        assert_eq!(touch(0, 1000).pressure(), 0);
+        assert_eq!(touch(500, 500).pressure(), 0);
+        assert_eq!(
+            Touch {
+                down: false,
+                ..touch(500, 1000)
+            }
+            .pressure(),
+            0
+        );
+    }
+}
@@ -1,7 +1,19 @@
 //! Interrupt synchronization APIs.
+//!
+//! These APIs cover the interrupt registers `IE`, `IF` and `IME` of the ARM9. The auxiliary
+//! DSi interrupts of [`AuxInterrupt`] are only wired to the ARM7, so there is no way to enable,
+//! query or acknowledge them from here.
 
+mod handler;
+
+pub use handler::*;
+
+use core::fmt::{Debug, Formatter, Result};
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
 
+/// Interrupt request flags register `IF`.
+const IF: *mut u32 = 0x0400_0214 as _;
+
 /// Wait for a vertical blank interrupt.
 #[inline(always)]
 pub fn swi_wait_for_vblank() {
@@ -46,7 +58,7 @@ where
 }
 
 #[repr(transparent)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Interrupt(u32);
 
 impl Interrupt {
@@ -64,6 +76,8 @@ impl Interrupt {
     pub const TIMER2: Self = Self(1 << 5);
     /// Timer 3 interrupt mask.
     pub const TIMER3: Self = Self(1 << 6);
+    /// Real time clock and serial interrupt mask, only raised on the ARM7.
+    pub const RTC: Self = Self(1 << 7);
     /// DMA 0 interrupt mask.
     pub const DMA0: Self = Self(1 << 8);
     /// DMA 1 interrupt mask.
@@ -86,6 +100,92 @@ impl Interrupt {
     pub const CARD: Self = Self(1 << 19);
     /// Interrupt mask.
     pub const CARD_LINE: Self = Self(1 << 20);
+    /// Geometry command FIFO interrupt mask.
+    pub const GEOMETRY_FIFO: Self = Self(1 << 21);
+    /// Lid opened interrupt mask, only raised on the ARM7.
+    pub const LID: Self = Self(1 << 22);
+    /// SPI bus interrupt mask, only raised on the ARM7.
+    pub const SPI: Self = Self(1 << 23);
+    /// Wireless interrupt mask, only raised on the ARM7.
+    pub const WIFI: Self = Self(1 << 24);
+    /// DSi DSP interrupt mask, sharing its bit with [`Interrupt::WIFI`] of the ARM7.
+    pub const DSP: Self = Self(1 << 24);
+    /// DSi camera interrupt mask.
+    pub const CAMERA: Self = Self(1 << 25);
+    /// DSi new DMA 0 interrupt mask.
+    pub const NDMA0: Self = Self(1 << 28);
+    /// DSi new DMA 1 interrupt mask.
+    pub const NDMA1: Self = Self(1 << 29);
+    /// DSi new DMA 2 interrupt mask.
+    pub const NDMA2: Self = Self(1 << 30);
+    /// DSi new DMA 3 interrupt mask.
+    pub const NDMA3: Self = Self(1 << 31);
+
+    /// Every named interrupt, in bit order, as listed by [`Debug`].
+    const NAMES: [(u32, &str); 28] = [
+        (Self::VBLANK.0, "VBLANK"),
+        (Self::HBLANK.0, "HBLANK"),
+        (Self::VCOUNT.0, "VCOUNT"),
+        (Self::TIMER0.0, "TIMER0"),
+        (Self::TIMER1.0, "TIMER1"),
+        (Self::TIMER2.0, "TIMER2"),
+        (Self::TIMER3.0, "TIMER3"),
+        (Self::RTC.0, "RTC"),
+        (Self::DMA0.0, "DMA0"),
+        (Self::DMA1.0, "DMA1"),
+        (Self::DMA2.0, "DMA2"),
+        (Self::DMA3.0, "DMA3"),
+        (Self::KEYS.0, "KEYS"),
+        (Self::CART.0, "CART"),
+        (Self::IPC_SYNC.0, "IPC_SYNC"),
+        (Self::FIFO_EMPTY.0, "FIFO_EMPTY"),
+        (Self::FIFO_NOT_EMPTY.0, "FIFO_NOT_EMPTY"),
+        (Self::CARD.0, "CARD"),
+        (Self::CARD_LINE.0, "CARD_LINE"),
+        (Self::GEOMETRY_FIFO.0, "GEOMETRY_FIFO"),
+        (Self::LID.0, "LID"),
+        (Self::SPI.0, "SPI"),
+        (Self::WIFI.0, "WIFI/DSP"),
+        (Self::CAMERA.0, "CAMERA"),
+        (Self::NDMA0.0, "NDMA0"),
+        (Self::NDMA1.0, "NDMA1"),
+        (Self::NDMA2.0, "NDMA2"),
+        (Self::NDMA3.0, "NDMA3"),
+    ];
+
+    /// Returns a mask with every interrupt.
+    #[inline]
+    pub const fn all() -> Self {
+        Self(0xF3FF_3FFF)
+    }
+
+    /// Returns the raw interrupt mask.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the interrupts that have been requested but not yet acknowledged.
+    #[inline]
+    pub fn pending() -> Self {
+        // SAFETY: `IF` is a valid hardware register and reading it has no side effects.
+        Self(unsafe { IF.read_volatile() })
+    }
+
+    /// Acknowledges the given requested interrupts.
+    ///
+    /// The libnds interrupt dispatcher already acknowledges every interrupt it handles.
+    #[inline]
+    pub fn acknowledge(mask: Self) {
+        // SAFETY: Writing set bits to `IF` only clears those requests.
+        unsafe { IF.write_volatile(mask.0) };
+    }
+}
+
+impl Debug for Interrupt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        flags(f, "Interrupt", self.0, &Self::NAMES)
+    }
 }
 
 impl BitOr for Interrupt {
@@ -119,3 +219,135 @@ impl BitAndAssign for Interrupt {
         self.0 &= rhs.0;
     }
 }
+
+/// Interrupt masks of the auxiliary interrupt enable register `IE2` of the DSi.
+///
+/// `IE2` only exists on the ARM7 of the DSi, so these masks can not be enabled from the ARM9.
+/// They describe the sources for code running on the ARM7 or exchanging masks with it, so unlike
+/// [`Interrupt`] they have no `pending`, `acknowledge` or handler functions.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AuxInterrupt(u32);
+
+impl AuxInterrupt {
+    /// GPIO18 pin 0 interrupt mask.
+    pub const GPIO18_0: Self = Self(1 << 0);
+    /// GPIO18 pin 1 interrupt mask.
+    pub const GPIO18_1: Self = Self(1 << 1);
+    /// GPIO18 pin 2 interrupt mask.
+    pub const GPIO18_2: Self = Self(1 << 2);
+    /// GPIO33 pin 0 interrupt mask.
+    pub const GPIO33_0: Self = Self(1 << 4);
+    /// Headphone connection interrupt mask, on GPIO33 pin 1.
+    pub const HEADPHONE: Self = Self(1 << 5);
+    /// Power button interrupt mask, on GPIO33 pin 2.
+    pub const POWER_BUTTON: Self = Self(1 << 6);
+    /// Sound enable interrupt mask, on GPIO33 pin 3.
+    pub const SOUND_ENABLE: Self = Self(1 << 7);
+    /// SD/MMC controller interrupt mask.
+    pub const SD_MMC: Self = Self(1 << 8);
+    /// SD card data line interrupt mask.
+    pub const SD_DATA: Self = Self(1 << 9);
+    /// SDIO controller interrupt mask, used by the DSi wireless module.
+    pub const SDIO: Self = Self(1 << 10);
+    /// SDIO data line interrupt mask.
+    pub const SDIO_DATA: Self = Self(1 << 11);
+    /// AES engine interrupt mask.
+    pub const AES: Self = Self(1 << 12);
+    /// I2C bus interrupt mask.
+    pub const I2C: Self = Self(1 << 13);
+    /// Extended microphone interrupt mask.
+    pub const MICROPHONE: Self = Self(1 << 14);
+
+    /// Every named interrupt, in bit order, as listed by [`Debug`].
+    const NAMES: [(u32, &str); 14] = [
+        (Self::GPIO18_0.0, "GPIO18_0"),
+        (Self::GPIO18_1.0, "GPIO18_1"),
+        (Self::GPIO18_2.0, "GPIO18_2"),
+        (Self::GPIO33_0.0, "GPIO33_0"),
+        (Self::HEADPHONE.0, "HEADPHONE"),
+        (Self::POWER_BUTTON.0, "POWER_BUTTON"),
+        (Self::SOUND_ENABLE.0, "SOUND_ENABLE"),
+        (Self::SD_MMC.0, "SD_MMC"),
+        (Self::SD_DATA.0, "SD_DATA"),
+        (Self::SDIO.0, "SDIO"),
+        (Self::SDIO_DATA.0, "SDIO_DATA"),
+        (Self::AES.0, "AES"),
+        (Self::I2C.0, "I2C"),
+        (Self::MICROPHONE.0, "MICROPHONE"),
+    ];
+
+    /// Returns a mask with every auxiliary interrupt.
+    #[inline]
+    pub const fn all() -> Self {
+        Self(0x7FF7)
+    }
+
+    /// Returns the raw interrupt mask.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+impl Debug for AuxInterrupt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        flags(f, "AuxInterrupt", self.0, &Self::NAMES)
+    }
+}
+
+impl BitOr for AuxInterrupt {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for AuxInterrupt {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for AuxInterrupt {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for AuxInterrupt {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+/// Writes a mask as the names of its set flags, such as `Interrupt(VBLANK | TIMER0)`,
+/// with unnamed bits as a trailing hexadecimal value.
+fn flags(f: &mut Formatter<'_>, name: &str, bits: u32, names: &[(u32, &str)]) -> Result {
+    write!(f, "{name}(")?;
+
+    let mut rest = bits;
+    let mut separator = "";
+    for &(bit, flag) in names {
+        if rest & bit != 0 {
+            write!(f, "{separator}{flag}")?;
+            rest &= !bit;
+            separator = " | ";
+        }
+    }
+
+    match (rest, bits) {
+        (0, 0) => f.write_str("empty")?,
+        (0, _) => {}
+        (rest, _) => write!(f, "{separator}{rest:#x}")?,
+    }
+
+    f.write_str(")")
+}
@@ -0,0 +1,314 @@
+use super::{Interrupt, critical_section};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+
+/// Number of handlers that can be registered per interrupt without `alloc`.
+#[cfg(not(feature = "alloc"))]
+pub const MAX_HANDLERS: usize = 4;
+
+/// The handlers of a single interrupt, in registration order.
+#[cfg(feature = "alloc")]
+type Chain = Vec<(u32, Box<dyn FnMut() + Send>)>;
+#[cfg(not(feature = "alloc"))]
+type Chain = [Option<(u32, fn())>; MAX_HANDLERS];
+
+/// An empty chain of handlers.
+#[cfg(feature = "alloc")]
+const EMPTY: Chain = Vec::new();
+#[cfg(not(feature = "alloc"))]
+const EMPTY: Chain = [None; MAX_HANDLERS];
+
+/// The handlers of every interrupt.
+static REGISTRY: Registry = Registry(UnsafeCell::new(State {
+    chains: [EMPTY; 32],
+    statics: [None; 32],
+    next: 0,
+    dispatching: false,
+}));
+
+struct State {
+    chains: [Chain; 32],
+    /// Handlers set with [`set_static_handler`], which are called before the chain.
+    statics: [Option<extern "C" fn()>; 32],
+    next: u32,
+    dispatching: bool,
+}
+
+struct Registry(UnsafeCell<State>);
+
+// SAFETY: The state is only accessed within critical sections or interrupt handlers,
+// which can not interrupt each other.
+unsafe impl Sync for Registry {}
+
+impl Registry {
+    /// Runs `f` with exclusive access to the state.
+    #[inline]
+    fn with<R>(&self, f: impl FnOnce(&mut State) -> R) -> R {
+        critical_section(|| {
+            // SAFETY: Interrupts are disabled, so nothing else accesses the state.
+            let state = unsafe { &mut *self.0.get() };
+            assert!(
+                !state.dispatching,
+                "Interrupt handlers can not be set or removed from within a handler"
+            );
+            f(state)
+        })
+    }
+}
+
+/// A registered interrupt handler, which is removed when the guard is dropped.
+///
+/// Use [`core::mem::forget`] to keep the handler registered forever.
+#[must_use = "The handler is removed immediately if the guard is not kept"]
+pub struct HandlerGuard {
+    bit: u8,
+    id: u32,
+    phantom: PhantomData<*mut ()>,
+}
+
+/// Registers a handler for an interrupt, which is called every time the interrupt occurs.
+///
+/// Multiple handlers can be registered for the same interrupt and are called in order of
+/// registration. The interrupt itself still has to be allowed with [`enable`](super::enable).
+/// Handlers must not set or remove handlers themselves.
+///
+/// # Panics
+/// Panics for the FIFO interrupts, whose libnds handlers run the FIFO system and must not be
+/// replaced.
+///
+/// # Examples
+/// ```ignore
+/// use nds::sys::interrupt::{self, Interrupt};
+///
+/// let guard = interrupt::set_handler(Interrupt::VBLANK, || {
+///     // Runs every vertical blank until the guard is dropped.
+/// });
+/// ```
+#[cfg(feature = "alloc")]
+pub fn set_handler(interrupt: Interrupt, handler: impl FnMut() + Send + 'static) -> HandlerGuard {
+    let bit = bit(interrupt);
+    let handler = Box::new(handler);
+
+    REGISTRY.with(|state| {
+        let id = state.next;
+        state.next = state.next.wrapping_add(1);
+
+        let chain = &mut state.chains[bit as usize];
+        chain.push((id, handler));
+        if chain.len() == 1 {
+            install(bit);
+        }
+
+        HandlerGuard::new(bit, id)
+    })
+}
+
+/// Registers a handler for an interrupt, which is called every time the interrupt occurs.
+///
+/// Multiple handlers can be registered for the same interrupt and are called in order of
+/// registration. The interrupt itself still has to be allowed with [`enable`](super::enable).
+/// Handlers must not set or remove handlers themselves.
+///
+/// # Panics
+/// Panics if [`MAX_HANDLERS`] handlers are already registered for the interrupt,
+/// and for the FIFO interrupts, whose libnds handlers run the FIFO system and must not be
+/// replaced.
+#[cfg(not(feature = "alloc"))]
+pub fn set_handler(interrupt: Interrupt, handler: fn()) -> HandlerGuard {
+    let bit = bit(interrupt);
+
+    REGISTRY.with(|state| {
+        let id = state.next;
+        state.next = state.next.wrapping_add(1);
+
+        let chain = &mut state.chains[bit as usize];
+        let first = is_empty(chain);
+        let slot = chain.iter_mut().find(|slot| slot.is_none());
+        *slot.expect("Too many handlers for a single interrupt") = Some((id, handler));
+        if first {
+            install(bit);
+        }
+
+        HandlerGuard::new(bit, id)
+    })
+}
+
+/// Sets the handler of an interrupt generated by the `#[interrupt]` attribute.
+///
+/// The handler is only recorded, so this can run before libnds initialized its interrupt
+/// table. [`install_static_handlers`] later installs it as the libnds handler itself,
+/// without a trampoline or allocation, as long as no other handlers are registered.
+///
+/// # Panics
+/// Panics if the interrupt already has a static handler, and for the FIFO interrupts.
+#[doc(hidden)]
+pub fn set_static_handler(interrupt: Interrupt, handler: extern "C" fn()) {
+    let bit = bit(interrupt);
+
+    REGISTRY.with(|state| {
+        let slot = &mut state.statics[bit as usize];
+        assert!(
+            slot.is_none(),
+            "Only one #[interrupt] handler can be set per interrupt"
+        );
+        *slot = Some(handler);
+    });
+}
+
+/// Installs the handlers set with [`set_static_handler`].
+///
+/// This is called by the `#[entry]` attribute before `main` runs.
+#[doc(hidden)]
+pub fn install_static_handlers() {
+    REGISTRY.with(|state| {
+        for bit in 0..32 {
+            let index = bit as usize;
+            if state.statics[index].is_some() && is_empty(&state.chains[index]) {
+                restore(state, bit);
+            }
+        }
+    });
+}
+
+impl HandlerGuard {
+    #[inline]
+    const fn new(bit: u8, id: u32) -> Self {
+        Self {
+            bit,
+            id,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl Drop for HandlerGuard {
+    fn drop(&mut self) {
+        REGISTRY.with(|state| {
+            let chain = &mut state.chains[self.bit as usize];
+
+            #[cfg(feature = "alloc")]
+            chain.retain(|(id, _)| *id != self.id);
+
+            #[cfg(not(feature = "alloc"))]
+            for slot in chain.iter_mut() {
+                if slot.is_some_and(|(id, _)| id == self.id) {
+                    *slot = None;
+                }
+            }
+
+            if is_empty(chain) {
+                restore(state, self.bit);
+            }
+        });
+    }
+}
+
+/// Returns the bit of an interrupt mask with exactly one interrupt.
+#[inline]
+fn bit(interrupt: Interrupt) -> u8 {
+    assert!(
+        interrupt.0.is_power_of_two(),
+        "Handlers can only be set for a single interrupt"
+    );
+    // libnds installs its own handlers for these, which can not be restored once replaced.
+    assert!(
+        (interrupt & (Interrupt::FIFO_EMPTY | Interrupt::FIFO_NOT_EMPTY)).0 == 0,
+        "Handlers of the FIFO interrupts are owned by libnds"
+    );
+    interrupt.0.trailing_zeros() as u8
+}
+
+/// Returns whether a chain has no handlers.
+#[cfg(feature = "alloc")]
+#[inline]
+fn is_empty(chain: &Chain) -> bool {
+    chain.is_empty()
+}
+#[cfg(not(feature = "alloc"))]
+#[inline]
+fn is_empty(chain: &Chain) -> bool {
+    chain.iter().all(Option::is_none)
+}
+
+/// Installs the static handler of an interrupt without other handlers as its libnds handler.
+#[inline]
+fn restore(state: &State, bit: u8) {
+    let handler = state.statics[bit as usize].map(|handler| handler as unsafe extern "C" fn());
+    // SAFETY: Removing the handler only stops the dispatch from libnds, while keeping the
+    // interrupt enabled for the rest of the system. Static handlers are plain functions.
+    unsafe { nds_sys::irqSet(1 << bit, handler) };
+}
+
+/// Installs the trampoline of an interrupt as its libnds handler.
+#[inline]
+fn install(bit: u8) {
+    // SAFETY: The trampoline only accesses the registry.
+    unsafe { nds_sys::irqSet(1 << bit, Some(TRAMPOLINES[bit as usize])) };
+}
+
+/// Calls the handlers of an interrupt.
+fn dispatch(bit: usize) {
+    // SAFETY: Interrupt handlers are not interrupted and the registry is only modified
+    // with interrupts disabled, so nothing else accesses the state.
+    let state = unsafe { &mut *REGISTRY.0.get() };
+    state.dispatching = true;
+
+    if let Some(handler) = state.statics[bit] {
+        handler();
+    }
+
+    #[cfg(feature = "alloc")]
+    for (_, handler) in state.chains[bit].iter_mut() {
+        handler();
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    for (_, handler) in state.chains[bit].iter().flatten() {
+        handler();
+    }
+
+    state.dispatching = false;
+}
+
+unsafe extern "C" fn trampoline<const BIT: usize>() {
+    dispatch(BIT);
+}
+
+/// The libnds handler of every interrupt bit, dispatching to the registered handlers.
+const TRAMPOLINES: [unsafe extern "C" fn(); 32] = [
+    trampoline::<0>,
+    trampoline::<1>,
+    trampoline::<2>,
+    trampoline::<3>,
+    trampoline::<4>,
+    trampoline::<5>,
+    trampoline::<6>,
+    trampoline::<7>,
+    trampoline::<8>,
+    trampoline::<9>,
+    trampoline::<10>,
+    trampoline::<11>,
+    trampoline::<12>,
+    trampoline::<13>,
+    trampoline::<14>,
+    trampoline::<15>,
+    trampoline::<16>,
+    trampoline::<17>,
+    trampoline::<18>,
+    trampoline::<19>,
+    trampoline::<20>,
+    trampoline::<21>,
+    trampoline::<22>,
+    trampoline::<23>,
+    trampoline::<24>,
+    trampoline::<25>,
+    trampoline::<26>,
+    trampoline::<27>,
+    trampoline::<28>,
+    trampoline::<29>,
+    trampoline::<30>,
+    trampoline::<31>,
+];
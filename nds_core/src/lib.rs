@@ -1,18 +1,31 @@
 //! # NDS
+//!
+//! Hardware access requires the `sys` feature, which is enabled by default.
+//! Without it, only the hardware independent parts of the crate are built,
+//! which allows them to be tested on the host.
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
 
+#[cfg(feature = "sys")]
 pub mod allocator;
+#[cfg(feature = "sys")]
 pub mod background;
 pub mod console;
+pub mod image;
 pub mod input;
+#[cfg(feature = "sys")]
 pub mod interrupt;
+#[cfg(feature = "log")]
+pub mod logger;
+#[cfg(feature = "sys")]
 pub mod math;
 pub mod process;
+#[cfg(feature = "sys")]
 pub mod system;
+#[cfg(feature = "sys")]
 pub mod video;
 
 #[cfg(feature = "proc")]
-pub use nds_proc::{dtcm, entry, itcm};
+pub use nds_proc::{dtcm, entry, include_font, interrupt, itcm};
@@ -0,0 +1,112 @@
+//! A [`log`](::log) backend writing to a console, the emulator debug port, or both.
+//!
+//! ```ignore
+//! use nds::sys::console::Console;
+//! use nds::sys::logger::{self, LogOutput};
+//!
+//! logger::init(LogOutput::Both(Console::demo()), log::LevelFilter::Info).unwrap();
+//! log::info!("Hello from the log facade!");
+//! ```
+//!
+//! The maximum level can also be limited at compile time with the `max_level_*` features,
+//! which removes more verbose log statements entirely.
+
+use crate::console::{Color, Console, DebugPort, Styled};
+use crate::interrupt::critical_section;
+use core::cell::UnsafeCell;
+use core::fmt::Write;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// The logger registered with the log facade.
+static LOGGER: Logger = Logger(UnsafeCell::new(None));
+
+/// Where log records are written to.
+pub enum LogOutput {
+    /// Records are printed to a console, with the level colored.
+    Console(Console),
+    /// Records are sent to the debug message port of emulators such as no$gba and melonDS.
+    DebugPort,
+    /// Records are printed to a console and sent to the debug message port.
+    Both(Console),
+}
+
+struct Logger(UnsafeCell<Option<LogOutput>>);
+
+// SAFETY: The output is only accessed within critical sections.
+unsafe impl Sync for Logger {}
+
+/// Installs the logger for the log facade, writing records up to `level` to `output`.
+///
+/// Only the first call succeeds, later calls return an error and drop `output`.
+pub fn init(output: LogOutput, level: LevelFilter) -> Result<(), SetLoggerError> {
+    critical_section(|| {
+        // SAFETY: Interrupts are disabled and there is only a single core, so the logger
+        // can not be set concurrently.
+        unsafe { log::set_logger_racy(&LOGGER)? };
+
+        // SAFETY: As above, and no record is being logged while the output is set.
+        unsafe {
+            *LOGGER.0.get() = Some(output);
+            log::set_max_level_racy(level);
+        }
+
+        Ok(())
+    })
+}
+
+/// Returns the console color of a level.
+#[inline]
+fn color(level: Level) -> Color {
+    match level {
+        Level::Error => Color::RedLight,
+        Level::Warn => Color::YellowLight,
+        Level::Info => Color::GreenLight,
+        Level::Debug => Color::CyanLight,
+        Level::Trace => Color::White,
+    }
+}
+
+impl Log for Logger {
+    #[inline]
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let level = record.level();
+        critical_section(|| {
+            // SAFETY: Interrupts are disabled, so the output is not accessed concurrently.
+            let output = unsafe { &mut *self.0.get() };
+
+            let (console, debug_port) = match output {
+                Some(LogOutput::Console(console)) => (Some(console), false),
+                Some(LogOutput::DebugPort) => (None, true),
+                Some(LogOutput::Both(console)) => (Some(console), true),
+                None => (None, false),
+            };
+
+            if let Some(console) = console {
+                let name = Styled::new(level.as_str()).foreground(color(level));
+                let padding = 5 - level.as_str().len();
+                writeln!(console, "{name}{:padding$} {}", "", record.args()).ok();
+            }
+
+            if debug_port {
+                writeln!(
+                    DebugPort,
+                    "[{level:<5} {}] {}",
+                    record.target(),
+                    record.args()
+                )
+                .ok();
+            }
+        });
+    }
+
+    #[inline]
+    fn flush(&self) {}
+}
@@ -1,3 +1,14 @@
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
+use portable_atomic::{AtomicBool, Ordering};
+
+/// Graphics power control register `POWCNT1`.
+const POWCNT: *const u32 = 0x0400_0304 as _;
+
+/// Whether sound was enabled through [`sound_enable`].
+///
+/// The sound control register is only accessible from the ARM7, so the state is tracked here.
+static SOUND: AtomicBool = AtomicBool::new(false);
+
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy)]
 pub struct Battery(u8);
@@ -29,3 +40,167 @@ pub enum BatteryLevel {
 pub fn battery() -> Battery {
     unsafe { Battery::from_raw(nds_sys::getBatteryLevel()) }
 }
+
+/// Graphics hardware blocks whose power can be switched independently.
+///
+/// The backlights are controlled by the ARM7 and switched through [`Backlight`] instead.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Power(u32);
+
+impl Power {
+    /// Both LCDs.
+    pub const LCD: Self = Self(nds_sys::PM_Bits_POWER_LCD);
+    /// Main 2D graphics engine (2D-A).
+    pub const ENGINE_2D_A: Self = Self(nds_sys::PM_Bits_POWER_2D_A);
+    /// Sub 2D graphics engine (2D-B).
+    pub const ENGINE_2D_B: Self = Self(nds_sys::PM_Bits_POWER_2D_B);
+    /// 3D rendering engine.
+    pub const RENDER_3D: Self = Self(nds_sys::PM_Bits_POWER_3D_CORE);
+    /// 3D geometry engine.
+    pub const GEOMETRY_3D: Self = Self(nds_sys::PM_Bits_POWER_MATRIX);
+    /// Both 2D graphics engines and the LCDs.
+    pub const ALL_2D: Self = Self(nds_sys::PM_Bits_POWER_ALL_2D);
+    /// All graphics engines and the LCDs.
+    pub const ALL: Self = Self(nds_sys::PM_Bits_POWER_ALL);
+}
+
+impl BitOr for Power {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Power {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Power {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Power {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+/// Powers on the given hardware blocks.
+#[inline]
+pub fn power_on(power: Power) {
+    unsafe { nds_sys::powerOn(power.0) };
+}
+
+/// Powers off the given hardware blocks.
+///
+/// Powering off an engine stops its output and access to its registers,
+/// so unused engines and screens can be turned off to save battery.
+#[inline]
+pub fn power_off(power: Power) {
+    unsafe { nds_sys::powerOff(power.0) };
+}
+
+/// Screen backlights, which are powered by the ARM7.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backlight(u32);
+
+impl Backlight {
+    /// Backlight of the top screen.
+    pub const TOP: Self = Self(nds_sys::PM_Bits_PM_BACKLIGHT_TOP);
+    /// Backlight of the bottom screen.
+    pub const BOTTOM: Self = Self(nds_sys::PM_Bits_PM_BACKLIGHT_BOTTOM);
+    /// Backlights of both screens.
+    pub const BOTH: Self = Self(Self::TOP.0 | Self::BOTTOM.0);
+}
+
+impl BitOr for Backlight {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Backlight {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Turns on the given backlights.
+///
+/// The request is forwarded to the ARM7 and takes effect asynchronously.
+#[inline]
+pub fn backlight_on(backlight: Backlight) {
+    unsafe { nds_sys::powerOn(backlight.0) };
+}
+
+/// Turns off the given backlights.
+///
+/// The request is forwarded to the ARM7 and takes effect asynchronously.
+#[inline]
+pub fn backlight_off(backlight: Backlight) {
+    unsafe { nds_sys::powerOff(backlight.0) };
+}
+
+/// Enables the sound hardware.
+///
+/// The request is forwarded to the ARM7 and takes effect asynchronously.
+#[inline]
+pub fn sound_enable() {
+    SOUND.store(true, Ordering::Relaxed);
+    unsafe { nds_sys::soundEnable() };
+}
+
+/// Disables the sound hardware.
+///
+/// The request is forwarded to the ARM7 and takes effect asynchronously.
+#[inline]
+pub fn sound_disable() {
+    SOUND.store(false, Ordering::Relaxed);
+    unsafe { nds_sys::soundDisable() };
+}
+
+/// Returns whether sound was enabled through [`sound_enable`] and not disabled since.
+#[inline]
+pub fn sound_enabled() -> bool {
+    SOUND.load(Ordering::Relaxed)
+}
+
+/// Puts the system to sleep until the lid is opened.
+///
+/// The graphics engines and screens are powered down while sleeping, as is sound if it was
+/// enabled through [`sound_enable`]. Both are restored to their previous state after waking.
+pub fn sleep() {
+    // SAFETY: Reading the power control register has no side effects.
+    let raw = unsafe { POWCNT.read_volatile() };
+    // The register holds the bare engine bits, while `powerOn` forwards anything without
+    // `PM_ARM9_DIRECT` to the ARM7.
+    let powered = Power(raw | nds_sys::PM_Bits_PM_ARM9_DIRECT) & Power::ALL;
+    let sound = sound_enabled();
+
+    power_off(Power::ALL);
+    if sound {
+        unsafe { nds_sys::soundDisable() };
+    }
+    unsafe { nds_sys::systemSleep() };
+    if sound {
+        unsafe { nds_sys::soundEnable() };
+    }
+    power_on(powered);
+}
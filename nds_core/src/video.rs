@@ -1,3 +1,8 @@
+pub mod capture;
+pub mod dual_screen;
+pub mod hblank;
+pub mod screenshot;
+
 #[repr(u32)]
 pub enum Mode {
     M0_2D = nds_sys::VideoMode_MODE_0_2D,
@@ -18,6 +23,7 @@ pub fn set_mode_sub(mode: Mode) {
 }
 
 #[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Bank(*mut u8);
 
 impl Bank {
@@ -30,6 +36,106 @@ impl Bank {
     pub const G: Self = Self(0x0400_0246 as *mut u8);
     pub const H: Self = Self(0x0400_0248 as *mut u8);
     pub const I: Self = Self(0x0400_0249 as *mut u8);
+
+    /// VRAM bank enable bit of `VRAMCNT`.
+    const ENABLE: u8 = 1 << 7;
+
+    /// Returns the address of the bank when it is mapped to LCDC mode.
+    fn lcdc(&self) -> *mut u16 {
+        const LCDC: [usize; 9] = [
+            0x0680_0000,
+            0x0682_0000,
+            0x0684_0000,
+            0x0686_0000,
+            0x0688_0000,
+            0x0689_0000,
+            0x0689_4000,
+            0x0689_8000,
+            0x068A_0000,
+        ];
+
+        let index = match self.0 as usize - 0x0400_0240 {
+            index @ 0..=6 => index,
+            index => index - 1,
+        };
+
+        LCDC[index] as *mut u16
+    }
+
+    /// Maps the bank to LCDC mode, returning the previous `VRAMCNT` value.
+    fn map_lcdc(&self) -> u8 {
+        // SAFETY: The bank points to a valid hardware VRAM control register.
+        let control = unsafe { self.0.read_volatile() };
+        self.set_control(Self::ENABLE);
+        control
+    }
+
+    /// Writes the raw `VRAMCNT` value of the bank.
+    fn set_control(&self, control: u8) {
+        // SAFETY: The bank points to a valid hardware VRAM control register.
+        unsafe { self.0.write_volatile(control) };
+    }
+
+    /// Returns the capture block index of the bank.
+    fn capture_block(&self) -> usize {
+        let block = self.0 as usize - 0x0400_0240;
+        assert!(block < 4, "Only VRAM banks A to D can be captured into");
+        block
+    }
+}
+
+/// One of the two 2D graphics engines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    Main,
+    Sub,
+}
+
+impl Engine {
+    /// Returns the base address of the display registers of the engine.
+    #[inline]
+    const fn registers(self) -> usize {
+        match self {
+            Self::Main => 0x0400_0000,
+            Self::Sub => 0x0400_1000,
+        }
+    }
+
+    /// Returns the base address of the standard palette memory of the engine.
+    #[inline]
+    const fn palette(self) -> usize {
+        match self {
+            Self::Main => 0x0500_0000,
+            Self::Sub => 0x0500_0400,
+        }
+    }
+}
+
+/// Makes the main engine drive the top screen and the sub engine the bottom screen.
+#[inline(always)]
+pub fn main_on_top() {
+    unsafe { nds_sys::lcdMainOnTop() };
+}
+
+/// Makes the main engine drive the bottom screen and the sub engine the top screen.
+#[inline(always)]
+pub fn main_on_bottom() {
+    unsafe { nds_sys::lcdMainOnBottom() };
+}
+
+/// Swaps the screens driven by the main and sub engine.
+#[inline(always)]
+pub fn swap_screens() {
+    unsafe { nds_sys::lcdSwap() };
+}
+
+/// Makes the given engine drive the top screen.
+#[inline]
+pub fn set_top_screen(engine: Engine) {
+    match engine {
+        Engine::Main => main_on_top(),
+        Engine::Sub => main_on_bottom(),
+    }
 }
 
 #[repr(i32)]
@@ -0,0 +1,156 @@
+//! Display capture APIs.
+//!
+//! The main engine can capture its own output, the raw 3D output, or a blend of either with a
+//! second VRAM or main memory source into one of the VRAM banks `A` to `D`.
+
+use super::Bank;
+use crate::interrupt::swi_wait_for_vblank;
+
+/// Display capture control register.
+const DISPCAPCNT: *mut u32 = 0x0400_0064 as _;
+
+/// Capture enable/busy bit of `DISPCAPCNT`.
+const ENABLE: u32 = 1 << 31;
+
+/// The first input of a capture (source A).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceA {
+    /// The composed main engine output (backgrounds, sprites and 3D).
+    Engine = 0,
+    /// The raw 3D render output.
+    Render3D = 1,
+}
+
+/// The second input of a capture (source B).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceB {
+    /// The VRAM bank selected for display in the main engine `DISPCNT`.
+    Vram = 0,
+    /// The main memory display FIFO.
+    MainMemory = 1,
+}
+
+/// Offset within a VRAM bank to read from or write to.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    O0x00000 = 0,
+    O0x08000 = 1,
+    O0x10000 = 2,
+    O0x18000 = 3,
+}
+
+impl Offset {
+    /// Returns the offset in bytes.
+    #[inline]
+    pub const fn bytes(self) -> usize {
+        self as usize * 0x8000
+    }
+}
+
+/// Dimensions of the captured area, starting at the top left corner of the screen.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    S128x128 = 0,
+    S256x64 = 1,
+    S256x128 = 2,
+    S256x192 = 3,
+}
+
+impl Size {
+    /// Returns the captured width in pixels.
+    #[inline]
+    pub const fn width(self) -> usize {
+        match self {
+            Self::S128x128 => 128,
+            _ => 256,
+        }
+    }
+
+    /// Returns the captured height in pixels.
+    #[inline]
+    pub const fn height(self) -> usize {
+        match self {
+            Self::S128x128 | Self::S256x128 => 128,
+            Self::S256x64 => 64,
+            Self::S256x192 => 192,
+        }
+    }
+}
+
+/// A display capture configuration over `DISPCAPCNT`.
+///
+/// The target bank has to be mapped to LCDC mode while the capture is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capture(u32);
+
+impl Capture {
+    /// Creates a full screen capture of the given source into bank `A`.
+    #[inline]
+    pub const fn new(source: SourceA) -> Self {
+        Self((source as u32) << 24 | (Size::S256x192 as u32) << 20)
+    }
+
+    /// Captures source B only instead of source A.
+    #[inline]
+    pub const fn only(self, source: SourceB) -> Self {
+        Self(self.0 & !(0b11 << 29 | 1 << 25) | 1 << 29 | (source as u32) << 25)
+    }
+
+    /// Blends source A with source B using the given factors.
+    ///
+    /// Both factors are in the range `0..=16` and the result is `(A * a + B * b) / 16`.
+    #[inline]
+    pub const fn blend(self, source: SourceB, a: u8, b: u8) -> Self {
+        assert!(a <= 16 && b <= 16, "Blend factors must be between 0 and 16");
+
+        let control = self.0 & !(0b11 << 29 | 1 << 25 | 0x1F1F);
+        Self(control | 2 << 29 | (source as u32) << 25 | (b as u32) << 8 | a as u32)
+    }
+
+    /// Sets the VRAM bank and offset the captured image is written to.
+    ///
+    /// Only banks `A`, `B`, `C` and `D` can be captured into.
+    #[inline]
+    pub fn target(self, bank: Bank, offset: Offset) -> Self {
+        let control = self.0 & !(0b1111 << 16);
+        Self(control | (bank.capture_block() as u32) << 16 | (offset as u32) << 18)
+    }
+
+    /// Sets the offset source B is read from when it is a VRAM bank.
+    #[inline]
+    pub const fn read_offset(self, offset: Offset) -> Self {
+        Self(self.0 & !(0b11 << 26) | (offset as u32) << 26)
+    }
+
+    /// Sets the size of the captured area.
+    #[inline]
+    pub const fn size(self, size: Size) -> Self {
+        Self(self.0 & !(0b11 << 20) | (size as u32) << 20)
+    }
+
+    /// Starts the capture at the beginning of the next frame.
+    #[inline]
+    pub fn start(self) {
+        // SAFETY: DISPCAPCNT points to a valid hardware display register.
+        unsafe { DISPCAPCNT.write_volatile(self.0 | ENABLE) };
+    }
+
+    /// Returns `true` while a capture is pending or in progress.
+    #[inline]
+    pub fn is_busy() -> bool {
+        // SAFETY: DISPCAPCNT points to a valid hardware display register.
+        unsafe { DISPCAPCNT.read_volatile() & ENABLE != 0 }
+    }
+
+    /// Waits for the running capture to complete.
+    #[inline]
+    pub fn wait() {
+        while Self::is_busy() {
+            swi_wait_for_vblank();
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Rendering 3D on both screens by alternating display capture.
+//!
+//! The 3D engine is only connected to the main engine, so every frame the main engine is swapped
+//! to the other screen while the previous frame is captured and shown by the sub engine.
+//! VRAM bank `C` is shown as a sub engine bitmap background and bank `D` as a grid of
+//! sub engine bitmap sprites, since bank `D` can not be mapped to sub engine backgrounds.
+//! Both screens effectively run at half the frame rate.
+
+use super::Bank;
+use super::capture::{Capture, Offset, SourceA};
+
+/// Sub engine display control register.
+const DISPCNT_SUB: *mut u32 = 0x0400_1000 as _;
+/// Sub engine background 2 control register.
+const BG2CNT_SUB: *mut u16 = 0x0400_100C as _;
+/// Sub engine background 2 affine parameters `PA`, `PB`, `PC` and `PD`.
+const BG2P_SUB: *mut [i16; 4] = 0x0400_1020 as _;
+/// Sub engine background 2 reference point `X` and `Y`.
+const BG2XY_SUB: *mut [i32; 2] = 0x0400_1028 as _;
+/// Sub engine object attribute memory.
+const OAM_SUB: *mut [u16; 4] = 0x0700_0400 as _;
+
+/// `VRAMCNT` value mapping bank `C` to sub engine backgrounds.
+const VRAM_C_SUB_BG: u8 = Bank::ENABLE | 4;
+/// `VRAMCNT` value mapping bank `D` to sub engine sprites.
+const VRAM_D_SUB_SPRITE: u8 = Bank::ENABLE | 4;
+
+/// The screen a frame of a [`DualScreen3D`] is rendered for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Top,
+    Bottom,
+}
+
+/// Renders 3D on both screens by capturing every other frame into VRAM bank `C` or `D`.
+///
+/// This takes over the sub engine and VRAM banks `C` and `D`.
+/// The main engine has to be set up for 3D by the caller.
+#[derive(Debug)]
+pub struct DualScreen3D {
+    target: Target,
+    source: SourceA,
+}
+
+impl DualScreen3D {
+    /// Sets up the sub engine to show captured frames, starting with a frame for the top screen.
+    pub fn new() -> Self {
+        // SAFETY: All pointers point to valid hardware display registers and sub engine OAM.
+        unsafe {
+            DISPCNT_SUB.write_volatile(1 << 16 | 1 << 12 | 1 << 10 | 1 << 5 | 5);
+            BG2CNT_SUB.write_volatile(1 << 14 | 1 << 7 | 1 << 2);
+            BG2P_SUB.write_volatile([1 << 8, 0, 0, 1 << 8]);
+            BG2XY_SUB.write_volatile([0, 0]);
+
+            // A 4x3 grid of 64x64 bitmap sprites covers the 256x192 capture in bank `D`.
+            for index in 0..128 {
+                let (x, y) = (index % 4, index / 4);
+                let attributes = match y < 3 {
+                    true => [
+                        3 << 10 | (64 * y) as u16,
+                        3 << 14 | (64 * x) as u16,
+                        1 << 12 | (8 * 32 * y + 8 * x) as u16,
+                        0,
+                    ],
+                    false => [1 << 9, 0, 0, 0],
+                };
+                OAM_SUB.add(index).write_volatile(attributes);
+            }
+        }
+
+        Self {
+            target: Target::Top,
+            source: SourceA::Engine,
+        }
+    }
+
+    /// Captures the raw 3D output instead of the composed main engine output.
+    #[inline]
+    pub fn render_3d_only(mut self) -> Self {
+        self.source = SourceA::Render3D;
+        self
+    }
+
+    /// Returns the screen the scene submitted this frame will be shown on.
+    #[inline]
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    /// Shows the previously submitted scene on its screen and starts capturing it.
+    ///
+    /// Call this once per frame right after the vertical blank that follows the scene
+    /// submitted for [`DualScreen3D::target`].
+    pub fn swap(&mut self) {
+        // The frame shown on one screen is captured into the bank that the sub engine
+        // is not showing, while the other bank keeps showing the previous frame.
+        let capture = match self.target {
+            Target::Top => {
+                super::main_on_top();
+                Bank::C.set_control(Bank::ENABLE);
+                Bank::D.set_control(VRAM_D_SUB_SPRITE);
+                Bank::C
+            }
+            Target::Bottom => {
+                super::main_on_bottom();
+                Bank::D.set_control(Bank::ENABLE);
+                Bank::C.set_control(VRAM_C_SUB_BG);
+                Bank::D
+            }
+        };
+
+        Capture::new(self.source)
+            .target(capture, Offset::O0x00000)
+            .start();
+
+        self.target = match self.target {
+            Target::Top => Target::Bottom,
+            Target::Bottom => Target::Top,
+        };
+    }
+}
+
+impl Default for DualScreen3D {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,333 @@
+//! Scanline raster effects driven by horizontal blank DMA.
+//!
+//! An [`HBlankTable`] holds one register value per scanline. A DMA channel set to start on every
+//! horizontal blank copies the value for the next scanline into the register, which is how wavy
+//! water, perspective floors and gradient skies are done without any CPU involvement.
+
+use super::Engine;
+use crate::interrupt::{self, HandlerGuard, Interrupt};
+use alloc::boxed::Box;
+use alloc::vec;
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+use core::ops::{Index, IndexMut};
+use portable_atomic::{AtomicU8, AtomicU32, Ordering};
+
+/// Number of visible scanlines.
+pub const LINES: usize = 192;
+
+/// Base address of the DMA channel registers, 12 bytes apart.
+const DMA: usize = 0x0400_00B0;
+
+/// DMA destination control: increment and reload after every transfer.
+const DMA_DST_RELOAD: u32 = 3 << 21;
+/// DMA repeat at every start condition.
+const DMA_REPEAT: u32 = 1 << 25;
+/// DMA 32-bit transfer width.
+const DMA_32BIT: u32 = 1 << 26;
+/// DMA start at horizontal blank.
+const DMA_START_HBLANK: u32 = 2 << 27;
+/// DMA enable.
+const DMA_ENABLE: u32 = 1 << 31;
+
+/// DMA channels used by a table, as a bit mask.
+static CLAIMED: AtomicU8 = AtomicU8::new(0);
+
+/// Front table, register and DMA control of every channel, restarted at every vertical blank.
+/// A control of zero means the channel is stopped.
+static TRANSFERS: [[AtomicU32; 3]; 4] = [const { [const { AtomicU32::new(0) }; 3] }; 4];
+
+/// The vertical blank handler restarting each channel.
+const RESTARTS: [fn(); 4] = [restart::<0>, restart::<1>, restart::<2>, restart::<3>];
+
+/// A hardware register that can be written once per scanline.
+pub struct Register<T> {
+    address: usize,
+    phantom: PhantomData<*mut T>,
+}
+
+impl<T> Register<T> {
+    /// Creates a register from its address.
+    ///
+    /// # Safety
+    /// `address` must be a valid, suitably aligned hardware register of type `T`.
+    #[inline]
+    pub const unsafe fn from_address(address: usize) -> Self {
+        Self {
+            address,
+            phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    const fn engine(engine: Engine, offset: usize) -> Self {
+        // SAFETY: Only called with offsets of valid display registers.
+        unsafe { Self::from_address(engine.registers() + offset) }
+    }
+}
+
+impl Register<u16> {
+    /// Horizontal scroll offset `BGxHOFS` of a background layer.
+    #[inline]
+    pub const fn horizontal_offset(engine: Engine, layer: usize) -> Self {
+        assert!(layer < 4, "Background layer must be between 0 and 3");
+        Self::engine(engine, 0x10 + layer * 4)
+    }
+
+    /// Vertical scroll offset `BGxVOFS` of a background layer.
+    #[inline]
+    pub const fn vertical_offset(engine: Engine, layer: usize) -> Self {
+        assert!(layer < 4, "Background layer must be between 0 and 3");
+        Self::engine(engine, 0x12 + layer * 4)
+    }
+
+    /// Horizontal window bounds `WINxH`, with the right edge in the low byte.
+    #[inline]
+    pub const fn window_horizontal(engine: Engine, window: usize) -> Self {
+        assert!(window < 2, "Window must be 0 or 1");
+        Self::engine(engine, 0x40 + window * 2)
+    }
+
+    /// Vertical window bounds `WINxV`, with the bottom edge in the low byte.
+    #[inline]
+    pub const fn window_vertical(engine: Engine, window: usize) -> Self {
+        assert!(window < 2, "Window must be 0 or 1");
+        Self::engine(engine, 0x44 + window * 2)
+    }
+
+    /// Alpha blending coefficients `BLDALPHA`.
+    #[inline]
+    pub const fn blend_alpha(engine: Engine) -> Self {
+        Self::engine(engine, 0x52)
+    }
+
+    /// An entry of the standard background palette.
+    #[inline]
+    pub const fn palette(engine: Engine, index: u8) -> Self {
+        // SAFETY: Palette memory holds 256 background colors per engine.
+        unsafe { Self::from_address(engine.palette() + index as usize * 2) }
+    }
+}
+
+impl Register<[u16; 2]> {
+    /// Horizontal and vertical scroll offsets `BGxHOFS` and `BGxVOFS` of a background layer.
+    #[inline]
+    pub const fn offset(engine: Engine, layer: usize) -> Self {
+        assert!(layer < 4, "Background layer must be between 0 and 3");
+        Self::engine(engine, 0x10 + layer * 4)
+    }
+}
+
+impl Register<Affine> {
+    /// Affine parameters and reference point of background layer 2 or 3.
+    #[inline]
+    pub const fn affine(engine: Engine, layer: usize) -> Self {
+        assert!(
+            layer == 2 || layer == 3,
+            "Only background layers 2 and 3 are affine"
+        );
+        Self::engine(engine, 0x20 + (layer - 2) * 0x10)
+    }
+}
+
+impl<T> Clone for Register<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Register<T> {}
+
+/// Affine parameters of a background layer in 8.8 fixed point,
+/// with the reference point in 20.8 fixed point.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Affine {
+    pub pa: i16,
+    pub pb: i16,
+    pub pc: i16,
+    pub pd: i16,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A double-buffered table of per-scanline register values applied by horizontal blank DMA.
+///
+/// Values are written into the back table through indexing,
+/// and [`HBlankTable::swap`] makes them visible at the next vertical blank.
+pub struct HBlankTable<T: Copy> {
+    channel: usize,
+    register: Register<T>,
+    // Two tables with one spare entry each for the horizontal blank after the last scanline.
+    tables: Box<[T]>,
+    front: usize,
+    _restart: HandlerGuard,
+}
+
+impl<T: Copy> HBlankTable<T> {
+    /// Creates a table for `register` on the given DMA channel with all scanlines set to `value`.
+    ///
+    /// The DMA channel is reserved until the table is dropped, and the table is restarted by a
+    /// vertical blank handler, so the [`VBLANK`](Interrupt::VBLANK) interrupt must stay enabled.
+    /// Values of word aligned types are copied with 32-bit transfers, all others with 16-bit ones.
+    /// Channel 3 is used by libnds for copies and should be avoided.
+    ///
+    /// # Panics
+    /// Panics if the channel is already used by another table.
+    pub fn new(channel: usize, register: Register<T>, value: T) -> Self {
+        const {
+            assert!(
+                size_of::<T>().is_multiple_of(2) && align_of::<T>() >= 2,
+                "Register values must be made of halfwords"
+            )
+        };
+        assert!(channel < 4, "DMA channel must be between 0 and 3");
+
+        let claimed = CLAIMED.fetch_or(1 << channel, Ordering::Relaxed);
+        assert!(
+            claimed & (1 << channel) == 0,
+            "DMA channel is already used by another table"
+        );
+
+        Self {
+            channel,
+            register,
+            tables: vec![value; (LINES + 1) * 2].into_boxed_slice(),
+            front: 0,
+            _restart: interrupt::set_handler(Interrupt::VBLANK, RESTARTS[channel]),
+        }
+    }
+
+    /// Returns the back table, which is shown after the next swap.
+    #[inline]
+    pub fn back(&self) -> &[T] {
+        let back = (self.front ^ 1) * (LINES + 1);
+        &self.tables[back..back + LINES]
+    }
+
+    /// Returns the back table mutably, which is shown after the next swap.
+    #[inline]
+    pub fn back_mut(&mut self) -> &mut [T] {
+        let back = (self.front ^ 1) * (LINES + 1);
+        &mut self.tables[back..back + LINES]
+    }
+
+    /// Makes the back table visible and restarts the DMA.
+    ///
+    /// Call this during the vertical blank of frames with a new back table. The visible table
+    /// is restarted at every vertical blank, so frames without a swap keep showing it.
+    /// The new back table starts out as a copy of the now visible table.
+    pub fn swap(&mut self) {
+        self.stop();
+        self.front ^= 1;
+
+        let (front, back) = match self.front {
+            0 => self.tables.split_at_mut(LINES + 1),
+            _ => {
+                let (back, front) = self.tables.split_at_mut(LINES + 1);
+                (front, back)
+            }
+        };
+        front[LINES] = front[0];
+        back.copy_from_slice(front);
+
+        let halfwords = size_of::<T>() / 2;
+        let source = front.as_ptr();
+
+        // SAFETY: The front table is a valid allocation that stays alive while the DMA runs.
+        unsafe { nds_sys::DC_FlushRange(source.cast(), size_of::<[T; LINES + 1]>() as u32) };
+
+        // Word transfers need word aligned addresses, which only a word aligned `T` guarantees.
+        let control = match align_of::<T>() >= 4 {
+            true => DMA_32BIT | (halfwords / 2) as u32,
+            false => halfwords as u32,
+        };
+
+        let [transfer_source, transfer_register, transfer_control] = &TRANSFERS[self.channel];
+        interrupt::critical_section(|| {
+            transfer_source.store(source as u32, Ordering::Relaxed);
+            transfer_register.store(self.register.address as u32, Ordering::Relaxed);
+            transfer_control.store(
+                DMA_ENABLE | DMA_START_HBLANK | DMA_REPEAT | DMA_DST_RELOAD | control,
+                Ordering::Relaxed,
+            );
+            restart_channel(self.channel);
+        });
+    }
+
+    /// Stops applying the table until the next swap.
+    #[inline]
+    pub fn stop(&mut self) {
+        interrupt::critical_section(|| {
+            TRANSFERS[self.channel][2].store(0, Ordering::Relaxed);
+            restart_channel(self.channel);
+        });
+    }
+}
+
+/// Restarts the transfer of a channel at the first scanline of its front table.
+fn restart_channel(channel: usize) {
+    let [source, register, control] = TRANSFERS[channel]
+        .each_ref()
+        .map(|value| value.load(Ordering::Relaxed));
+
+    let dma = (DMA + channel * 12) as *mut u32;
+    // SAFETY: The channel control register is valid and owned by a table.
+    unsafe { dma.add(2).write_volatile(0) };
+    if control == 0 {
+        return;
+    }
+
+    // The first scanline is written directly, the horizontal blank DMA handles the rest.
+    let halfwords = match control & DMA_32BIT != 0 {
+        true => (control & 0xFFFF) as usize * 2,
+        false => (control & 0xFFFF) as usize,
+    };
+    let first = source as *const u16;
+    for halfword in 0..halfwords {
+        // SAFETY: The register is valid for the table entries and the table holds at least one.
+        unsafe {
+            (register as *mut u16)
+                .add(halfword)
+                .write_volatile(first.add(halfword).read())
+        };
+    }
+
+    // SAFETY: The channel registers are valid and the channel is stopped.
+    // The front table stays alive until the table stops the channel when dropped.
+    unsafe {
+        dma.write_volatile(source + halfwords as u32 * 2);
+        dma.add(1).write_volatile(register);
+        dma.add(2).write_volatile(control);
+    }
+}
+
+/// Restarts the transfer of a channel at the vertical blank, so it never runs past its table.
+fn restart<const CHANNEL: usize>() {
+    restart_channel(CHANNEL);
+}
+
+impl<T: Copy> Index<usize> for HBlankTable<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, line: usize) -> &Self::Output {
+        &self.back()[line]
+    }
+}
+
+impl<T: Copy> IndexMut<usize> for HBlankTable<T> {
+    #[inline]
+    fn index_mut(&mut self, line: usize) -> &mut Self::Output {
+        &mut self.back_mut()[line]
+    }
+}
+
+impl<T: Copy> Drop for HBlankTable<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.stop();
+        CLAIMED.fetch_and(!(1 << self.channel), Ordering::Relaxed);
+    }
+}
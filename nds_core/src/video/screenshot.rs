@@ -0,0 +1,93 @@
+//! Screenshot capturing and image encoding.
+//!
+//! The encoders live in [`crate::image`], so they can be used outside of the hardware as well.
+
+use super::Bank;
+use super::capture::{Capture, Offset, Size, SourceA};
+use crate::image;
+use alloc::vec::Vec;
+
+/// A captured 15-bit `BGR555` bitmap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    width: usize,
+    height: usize,
+    pixels: Vec<u16>,
+}
+
+impl Screenshot {
+    /// Creates a screenshot from existing `BGR555` pixel data in row-major order.
+    ///
+    /// # Panics
+    /// Panics if either dimension is zero or the pixel count does not match the dimensions.
+    #[inline]
+    pub fn new(width: usize, height: usize, pixels: Vec<u16>) -> Self {
+        assert!(width > 0 && height > 0, "Dimensions must not be zero");
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "Pixel count must match the dimensions"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Captures the given source into `bank` and copies the result into a new screenshot.
+    ///
+    /// The bank is mapped to LCDC mode for the duration of the capture and restored afterwards.
+    /// This blocks until the capture completes at the end of the next frame.
+    pub fn capture(source: SourceA, bank: Bank, size: Size) -> Self {
+        let base = bank.lcdc();
+        let mapping = bank.map_lcdc();
+
+        Capture::new(source)
+            .target(bank, Offset::O0x00000)
+            .size(size)
+            .start();
+        Capture::wait();
+
+        let (width, height) = (size.width(), size.height());
+        let pixels = (0..width * height)
+            // SAFETY: The bank is mapped to LCDC and large enough to hold the captured area.
+            .map(|index| unsafe { base.add(index).read_volatile() } & 0x7FFF)
+            .collect();
+
+        bank.set_control(mapping);
+        Self::new(width, height, pixels)
+    }
+
+    /// Returns the width in pixels.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the raw `BGR555` pixels in row-major order.
+    #[inline]
+    pub fn pixels(&self) -> &[u16] {
+        &self.pixels
+    }
+
+    /// Encodes the screenshot as an uncompressed 24-bit BMP image.
+    #[inline]
+    pub fn to_bmp(&self) -> Vec<u8> {
+        image::encode_bmp(self.width, self.height, &self.pixels)
+    }
+
+    /// Encodes the screenshot as a 24-bit PNG image.
+    ///
+    /// The image data is stored without compression to keep the encoder small and fast.
+    #[inline]
+    pub fn to_png(&self) -> Vec<u8> {
+        image::encode_png(self.width, self.height, &self.pixels)
+    }
+}
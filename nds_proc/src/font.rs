@@ -0,0 +1,408 @@
+//! Conversion of PNG glyph sheets into console fonts.
+
+use png::{ColorType, Decoder, Transformations};
+use proc_macro::TokenStream;
+use quote::quote;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, Ident, LitInt, LitStr, Result, Token};
+
+/// Arguments of `include_font!("path.png", first = 32, count = 96, bpp = 4)`.
+pub struct FontArgs {
+    path: LitStr,
+    first: u16,
+    count: Option<u16>,
+    bpp: u8,
+}
+
+impl Parse for FontArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = Self {
+            path: input.parse()?,
+            first: 32,
+            count: None,
+            bpp: 4,
+        };
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: LitInt = input.parse()?;
+
+            match key.to_string().as_str() {
+                "first" => args.first = value.base10_parse()?,
+                "count" => args.count = Some(value.base10_parse()?),
+                "bpp" => args.bpp = value.base10_parse()?,
+                _ => return Err(Error::new(key.span(), "Expected `first`, `count` or `bpp`")),
+            }
+        }
+
+        if !matches!(args.bpp, 1 | 4 | 8) {
+            return Err(Error::new(input.span(), "`bpp` must be 1, 4 or 8"));
+        }
+
+        Ok(args)
+    }
+}
+
+/// A decoded glyph sheet as palette indices.
+struct Sheet {
+    width: usize,
+    height: usize,
+    indices: Vec<u8>,
+    palette: Vec<u16>,
+}
+
+pub fn include_font(args: FontArgs) -> Result<TokenStream> {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = PathBuf::from(root).join(args.path.value());
+    let error = |message: String| Error::new(args.path.span(), message);
+
+    let sheet = File::open(&path)
+        .map_err(Into::into)
+        .and_then(decode)
+        .map_err(|err| error(format!("{}: {err}", path.display())))?;
+
+    let (bytes, count) = convert(&sheet, args.count, args.bpp).map_err(error)?;
+    let tiles = bytes
+        .chunks(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]));
+    let palette = &sheet.palette;
+    let path = path.display().to_string();
+    let (first, count, bpp) = (args.first, count as u16, args.bpp);
+    let code_page = match first as usize + count as usize <= 0x80 {
+        true => quote!(ASCII),
+        false => quote!(CP437),
+    };
+
+    Ok(quote! {
+        {
+            const _: &[u8] = include_bytes!(#path);
+            ::nds::sys::console::ConsoleFont {
+                tiles: &[#(#tiles),*],
+                palette: &[#(#palette),*],
+                first: #first,
+                count: #count,
+                bpp: #bpp,
+                code_page: ::nds::sys::console::CodePage::#code_page,
+            }
+        }
+    }
+    .into())
+}
+
+/// Checks a decoded sheet and converts its first `count` glyphs, or all of them, into tile data.
+///
+/// Returns the tile data and the number of converted glyphs.
+fn convert(
+    sheet: &Sheet,
+    count: Option<u16>,
+    bpp: u8,
+) -> std::result::Result<(Vec<u8>, usize), String> {
+    if sheet.indices.len() != sheet.width * sheet.height {
+        return Err("Glyph sheet pixel data does not match its dimensions".into());
+    }
+
+    if !sheet.width.is_multiple_of(8) || !sheet.height.is_multiple_of(8) {
+        return Err("Glyph sheet dimensions must be multiples of 8".into());
+    }
+
+    let colors = 1usize << bpp;
+    if sheet.palette.len() > colors {
+        return Err(format!("Glyph sheet has more than {colors} colors"));
+    }
+
+    let glyphs = (sheet.width / 8) * (sheet.height / 8);
+    let count = count.map_or(glyphs, usize::from);
+    if count > glyphs {
+        return Err(format!("Glyph sheet only contains {glyphs} glyphs"));
+    }
+
+    Ok((tiles(sheet, count, bpp), count))
+}
+
+/// Decodes a PNG into palette indices, where index 0 is the background color.
+///
+/// Indexed images keep their palette, while for other images the palette is built in order of
+/// appearance, starting with transparent pixels or the color of the top left pixel.
+fn decode(source: impl Read) -> std::result::Result<Sheet, Box<dyn std::error::Error>> {
+    let mut decoder = Decoder::new(source);
+
+    // Indexed images keep their bit depth and are unpacked below, all others are expanded
+    // to one byte per sample.
+    let transformations = match decoder.read_header_info()?.color_type {
+        ColorType::Indexed => Transformations::STRIP_16,
+        _ => Transformations::normalize_to_color8(),
+    };
+    decoder.set_transformations(transformations);
+
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer)?;
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let info = reader.info();
+
+    if frame.color_type == ColorType::Indexed {
+        let palette = info.palette.as_deref().unwrap_or_default();
+        let palette = palette.chunks(3).map(|rgb| bgr555(rgb[0], rgb[1], rgb[2]));
+        let depth = frame.bit_depth as usize;
+        let indices = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let bit = x * depth;
+                let byte = buffer[y * frame.line_size + bit / 8];
+                (byte >> (8 - depth - bit % 8)) & ((1 << depth) - 1) as u8
+            })
+            .collect();
+
+        return Ok(Sheet {
+            width,
+            height,
+            indices,
+            palette: palette.collect(),
+        });
+    }
+
+    let channels = frame.color_type.samples();
+    let rgba = buffer.chunks(channels).map(|pixel| match frame.color_type {
+        ColorType::Grayscale => [pixel[0], pixel[0], pixel[0], 255],
+        ColorType::GrayscaleAlpha => [pixel[0], pixel[0], pixel[0], pixel[1]],
+        ColorType::Rgb => [pixel[0], pixel[1], pixel[2], 255],
+        _ => [pixel[0], pixel[1], pixel[2], pixel[3]],
+    });
+    let rgba: Vec<_> = rgba.take(width * height).collect();
+
+    let background = match rgba.iter().any(|pixel| pixel[3] < 128) {
+        true => None,
+        false => rgba.first().map(|&[r, g, b, _]| bgr555(r, g, b)),
+    };
+
+    // Without a background color, index 0 is reserved for transparent pixels.
+    let start = background.is_none() as usize;
+    let mut palette = vec![background.unwrap_or(0)];
+    let indices = rgba
+        .iter()
+        .map(|&[r, g, b, a]| {
+            if a < 128 {
+                return 0;
+            }
+
+            let color = bgr555(r, g, b);
+            match palette[start..].iter().position(|&entry| entry == color) {
+                Some(index) => (index + start) as u8,
+                None => {
+                    palette.push(color);
+                    (palette.len() - 1) as u8
+                }
+            }
+        })
+        .collect();
+
+    Ok(Sheet {
+        width,
+        height,
+        indices,
+        palette,
+    })
+}
+
+/// Converts the first `count` glyphs of the sheet into tile data, row by row.
+fn tiles(sheet: &Sheet, count: usize, bpp: u8) -> Vec<u8> {
+    let columns = sheet.width / 8;
+    let mut out = Vec::new();
+
+    for glyph in 0..count {
+        let (left, top) = ((glyph % columns) * 8, (glyph / columns) * 8);
+        for y in top..top + 8 {
+            let row = &sheet.indices[y * sheet.width + left..][..8];
+            match bpp {
+                1 => out.push(
+                    row.iter()
+                        .rev()
+                        .fold(0, |acc, &i| acc << 1 | (i != 0) as u8),
+                ),
+                4 => out.extend(row.chunks(2).map(|pair| pair[0] & 0xF | pair[1] << 4)),
+                _ => out.extend_from_slice(row),
+            }
+        }
+    }
+
+    // Tile data is stored as halfwords.
+    if out.len() % 2 != 0 {
+        out.push(0);
+    }
+
+    out
+}
+
+/// Converts an 8-bit RGB color into a 15-bit `BGR555` color.
+fn bgr555(r: u8, g: u8, b: u8) -> u16 {
+    (r as u16 >> 3) | (g as u16 >> 3) << 5 | (b as u16 >> 3) << 10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use png::{BitDepth, Encoder};
+
+    /// Encodes an image with the given raw scanlines.
+    fn png(width: u32, height: u32, color: ColorType, depth: BitDepth, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(depth);
+        if color == ColorType::Indexed {
+            encoder.set_palette([0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 255].as_slice());
+        }
+
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+        bytes
+    }
+
+    fn unpack(width: u32, height: u32, depth: BitDepth, data: &[u8]) -> Vec<u8> {
+        let png = png(width, height, ColorType::Indexed, depth, data);
+        decode(png.as_slice()).unwrap().indices
+    }
+
+    /// A sheet of a single glyph with the given first row, and all other pixels at index 0.
+    fn glyph(row: [u8; 8], palette: usize) -> Sheet {
+        let mut indices = vec![0; 64];
+        indices[..8].copy_from_slice(&row);
+        Sheet {
+            width: 8,
+            height: 8,
+            indices,
+            palette: vec![0; palette],
+        }
+    }
+
+    #[test]
+    fn unpacks_1bit_indices() {
+        let indices = unpack(8, 1, BitDepth::One, &[0b1010_0011]);
+        assert_eq!(indices, [1, 0, 1, 0, 0, 0, 1, 1]);
+
+        // Rows start at byte boundaries.
+        let indices = unpack(3, 2, BitDepth::One, &[0b1010_0000, 0b0100_0000]);
+        assert_eq!(indices, [1, 0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn unpacks_2bit_indices() {
+        let indices = unpack(8, 1, BitDepth::Two, &[0b00_01_10_11, 0b11_10_01_00]);
+        assert_eq!(indices, [0, 1, 2, 3, 3, 2, 1, 0]);
+
+        let indices = unpack(3, 2, BitDepth::Two, &[0b01_10_11_00, 0b11_00_01_00]);
+        assert_eq!(indices, [1, 2, 3, 3, 0, 1]);
+    }
+
+    #[test]
+    fn unpacks_4bit_indices() {
+        let indices = unpack(4, 1, BitDepth::Four, &[0x01, 0x23]);
+        assert_eq!(indices, [0, 1, 2, 3]);
+
+        let indices = unpack(3, 2, BitDepth::Four, &[0x12, 0x30, 0x32, 0x10]);
+        assert_eq!(indices, [1, 2, 3, 3, 2, 1]);
+    }
+
+    #[test]
+    fn decodes_indexed_palette() {
+        let png = png(2, 1, ColorType::Indexed, BitDepth::Eight, &[0, 3]);
+        let sheet = decode(png.as_slice()).unwrap();
+        assert_eq!(sheet.palette, [0x0000, 0x7FFF, 0x001F, 0x7C00]);
+        assert_eq!(sheet.indices, [0, 3]);
+    }
+
+    #[test]
+    fn decodes_colors_in_order_of_appearance() {
+        let png = png(4, 1, ColorType::Grayscale, BitDepth::One, &[0b1001_0000]);
+        let sheet = decode(png.as_slice()).unwrap();
+        assert_eq!(sheet.palette, [0x7FFF, 0x0000]);
+        assert_eq!(sheet.indices, [0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn packs_1bpp_leftmost_pixel_first() {
+        let (tiles, _) = convert(&glyph([1, 0, 0, 0, 0, 0, 0, 0], 2), None, 1).unwrap();
+        assert_eq!(tiles[0], 0x01);
+
+        let (tiles, _) = convert(&glyph([0, 0, 0, 0, 0, 0, 1, 1], 2), None, 1).unwrap();
+        assert_eq!(tiles[0], 0xC0);
+        assert_eq!(tiles.len(), 8);
+    }
+
+    #[test]
+    fn packs_4bpp_nibbles() {
+        let (tiles, _) = convert(&glyph([1, 2, 3, 4, 5, 6, 7, 8], 9), None, 4).unwrap();
+        assert_eq!(tiles[..4], [0x21, 0x43, 0x65, 0x87]);
+        assert_eq!(tiles.len(), 32);
+    }
+
+    #[test]
+    fn packs_8bpp_bytes() {
+        let (tiles, _) = convert(&glyph([1, 2, 3, 4, 5, 6, 7, 8], 9), None, 8).unwrap();
+        assert_eq!(tiles[..8], [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(tiles.len(), 64);
+    }
+
+    #[test]
+    fn converts_requested_glyphs() {
+        let sheet = Sheet {
+            width: 16,
+            height: 8,
+            indices: (0..128).map(|index| (index % 16 >= 8) as u8).collect(),
+            palette: vec![0; 2],
+        };
+
+        let (tiles, count) = convert(&sheet, None, 1).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(tiles, [[0x00; 8], [0xFF; 8]].concat());
+
+        let (tiles, count) = convert(&sheet, Some(1), 1).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(tiles, [0x00; 8]);
+    }
+
+    #[test]
+    fn rejects_too_many_colors() {
+        let error = convert(&glyph([0; 8], 3), None, 1).unwrap_err();
+        assert_eq!(error, "Glyph sheet has more than 2 colors");
+
+        let error = convert(&glyph([0; 8], 17), None, 4).unwrap_err();
+        assert_eq!(error, "Glyph sheet has more than 16 colors");
+    }
+
+    #[test]
+    fn rejects_too_many_glyphs() {
+        let error = convert(&glyph([0; 8], 2), Some(2), 4).unwrap_err();
+        assert_eq!(error, "Glyph sheet only contains 1 glyphs");
+    }
+
+    #[test]
+    fn rejects_bad_dimensions() {
+        let mut sheet = glyph([0; 8], 2);
+        sheet.indices.pop();
+        let error = convert(&sheet, None, 4).unwrap_err();
+        assert_eq!(
+            error,
+            "Glyph sheet pixel data does not match its dimensions"
+        );
+
+        let sheet = Sheet {
+            width: 4,
+            height: 8,
+            indices: vec![0; 32],
+            palette: vec![0; 2],
+        };
+        let error = convert(&sheet, None, 4).unwrap_err();
+        assert_eq!(error, "Glyph sheet dimensions must be multiples of 8");
+    }
+}
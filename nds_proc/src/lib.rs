@@ -1,7 +1,11 @@
+mod font;
+
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Error, Item, ItemFn, ItemStatic, parse_macro_input};
+use syn::{Error, Ident, Item, ItemFn, ItemStatic, ReturnType, Token, parse_macro_input};
 
 /// Marks the entry point of a Nintendo DS homebrew program.
 #[proc_macro_attribute]
@@ -20,12 +24,98 @@ pub fn entry(_args: TokenStream, input: TokenStream) -> TokenStream {
             use ::nds::sys::process::Termination;
             #[inline(always)]
             #function
+            ::nds::sys::interrupt::install_static_handlers();
             #ident().status()
         }
     }
     .into()
 }
 
+/// Registers a function as handler of an interrupt at startup.
+///
+/// The function is turned into an `extern "C"` function and placed into ITCM if `itcm` is given.
+/// It is recorded before `main` runs and installed as the libnds handler of the interrupt by
+/// [`entry`] once libnds is initialized, so it is called without any indirection or allocation.
+/// Handlers added with `nds::sys::interrupt::set_handler` run after it.
+/// Only one such function can be registered per interrupt, and it must not take arguments
+/// or return a value.
+///
+/// ```ignore
+/// #[nds::interrupt(VBLANK, itcm)]
+/// fn vblank() {
+///     // Runs every vertical blank.
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match Punctuated::<Ident, Token![,]>::parse_terminated.parse(args) {
+        Ok(args) => args,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let function = parse_macro_input!(input as ItemFn);
+    let sig = &function.sig;
+
+    let mut args = args.into_iter();
+    let Some(source) = args.next() else {
+        let message = "Expected an interrupt, such as #[interrupt(VBLANK)]";
+        return compile_error(&sig.ident, message);
+    };
+
+    let mut itcm = false;
+    for arg in args {
+        match arg == "itcm" && !itcm {
+            true => itcm = true,
+            false => return compile_error(&arg, "Expected `itcm`"),
+        }
+    }
+
+    if let Some(unsafety) = &sig.unsafety {
+        return compile_error(unsafety, "Interrupt handlers can not be `unsafe`");
+    }
+    if let Some(constness) = &sig.constness {
+        return compile_error(constness, "Interrupt handlers can not be `const`");
+    }
+    if let Some(variadic) = &sig.variadic {
+        return compile_error(variadic, "Interrupt handlers can not be variadic");
+    }
+    if !sig.inputs.is_empty() {
+        return compile_error(&sig.inputs, "Interrupt handlers can not take arguments");
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        return compile_error(ty, "Interrupt handlers can not return a value");
+    }
+    if sig.asyncness.is_some() || !sig.generics.params.is_empty() {
+        return compile_error(
+            sig,
+            "Interrupt handlers must be plain, non-generic functions",
+        );
+    }
+
+    let ItemFn {
+        attrs, vis, block, ..
+    } = &function;
+    let ident = &sig.ident;
+    let section = itcm.then(|| quote!(#[unsafe(link_section = ".itcm.text")]));
+
+    quote! {
+        #(#attrs)*
+        #section
+        #vis extern "C" fn #ident() #block
+
+        const _: () = {
+            extern "C" fn register() {
+                let interrupt = ::nds::sys::interrupt::Interrupt::#source;
+                ::nds::sys::interrupt::set_static_handler(interrupt, #ident);
+            }
+
+            #[used]
+            #[unsafe(link_section = ".init_array")]
+            static REGISTER: extern "C" fn() = register;
+        };
+    }
+    .into()
+}
+
 /// Places a `fn` or `static` into `Instruction Tightly Coupled Memory` (ITCM).
 ///
 /// ITCM is a 32KB fast memory region on the ARM9 core of the Nintendo DS,
@@ -34,9 +124,9 @@ pub fn entry(_args: TokenStream, input: TokenStream) -> TokenStream {
 pub fn itcm(_args: TokenStream, input: TokenStream) -> TokenStream {
     let message = "The #[itcm] attribute can only be used on functions or static variables";
     match parse_macro_input!(input as Item) {
-        Item::Fn(item_fn) => quote!(#[link_section = ".itcm.text"] #item_fn),
+        Item::Fn(item_fn) => quote!(#[unsafe(link_section = ".itcm.text")] #item_fn),
         // todo!(): Decide on how `.itcm.bss` is applied.
-        Item::Static(item_static) => quote!(#[link_section = ".itcm.data"] #item_static),
+        Item::Static(item_static) => quote!(#[unsafe(link_section = ".itcm.data")] #item_static),
         other => compile_error(&other, message).into(),
     }
     .into()
@@ -50,7 +140,24 @@ pub fn itcm(_args: TokenStream, input: TokenStream) -> TokenStream {
 pub fn dtcm(_args: TokenStream, input: TokenStream) -> TokenStream {
     let item_static = parse_macro_input!(input as ItemStatic);
     // todo!(): Decide on how `.sbss` is applied.
-    quote!(#[link_section = ".dtcm.data"] #item_static).into()
+    quote!(#[unsafe(link_section = ".dtcm.data")] #item_static).into()
+}
+
+/// Converts a PNG glyph sheet into a `ConsoleFont` at compile time.
+///
+/// The sheet is a grid of 8x8 glyphs read left to right, top to bottom, with the path being
+/// relative to the crate root. Optional arguments set the character code of the first glyph
+/// (`first`, default 32), the number of glyphs (`count`, default all) and the bits per pixel
+/// of the generated tiles (`bpp`, either 1, 4 or 8, default 4).
+/// Fonts ending within ASCII use the ASCII code page, all others code page 437.
+///
+/// ```ignore
+/// static FONT: ConsoleFont = nds::include_font!("assets/font.png", first = 32, bpp = 4);
+/// ```
+#[proc_macro]
+pub fn include_font(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as font::FontArgs);
+    font::include_font(args).unwrap_or_else(|error| error.to_compile_error().into())
 }
 
 fn compile_error(tokens: &impl Spanned, message: &str) -> TokenStream {
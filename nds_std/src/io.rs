@@ -0,0 +1,125 @@
+//! Global standard output and error streams.
+//!
+//! Standard output is written to a [`Console`], which defaults to the demo console
+//! and can be replaced with [`set_stdout`]. Standard error follows standard output
+//! unless redirected with [`set_stderr`], for example to the emulator debug port.
+//!
+//! Output is dropped instead of blocking while the stream is already being written to,
+//! for example when an interrupt handler prints while the program is printing, or when a
+//! [`Display`](core::fmt::Display) implementation prints while it is being printed.
+//! The print macros ignore this, while writing through a handle returns an error.
+
+use crate::sync::Mutex;
+use core::fmt::{Arguments, Error, Result, Write};
+use nds_core::console::{Console, DebugPort};
+
+/// The console standard output is written to, initialized on first use.
+static STDOUT: Mutex<Option<Console>> = Mutex::new(None);
+/// The output standard error is written to.
+static STDERR: Mutex<Output> = Mutex::new(Output::Stdout);
+
+/// A destination for standard error.
+pub enum Output {
+    /// The same console as standard output.
+    Stdout,
+    /// A separate console.
+    Console(Console),
+    /// The debug message port of emulators such as no$gba and melonDS.
+    DebugPort,
+}
+
+impl From<Console> for Output {
+    #[inline]
+    fn from(console: Console) -> Self {
+        Self::Console(console)
+    }
+}
+
+/// A handle to the global standard output stream.
+#[derive(Debug, Clone, Copy)]
+pub struct Stdout;
+
+/// A handle to the global standard error stream.
+#[derive(Debug, Clone, Copy)]
+pub struct Stderr;
+
+/// Returns a handle to the standard output of the program.
+#[inline]
+pub fn stdout() -> Stdout {
+    Stdout
+}
+
+/// Returns a handle to the standard error of the program.
+#[inline]
+pub fn stderr() -> Stderr {
+    Stderr
+}
+
+/// Replaces the console standard output is written to, returning the previous one.
+///
+/// If nothing was printed yet, no previous console exists and the demo console is never initialized.
+#[inline]
+pub fn set_stdout(console: Console) -> Option<Console> {
+    STDOUT.lock().replace(console)
+}
+
+/// Redirects standard error, returning the previous output.
+///
+/// # Examples
+/// ```
+/// use nds::std::io::{self, Output};
+///
+/// io::set_stderr(Output::DebugPort);
+/// ```
+#[inline]
+pub fn set_stderr(output: impl Into<Output>) -> Output {
+    core::mem::replace(&mut *STDERR.lock(), output.into())
+}
+
+/// Writes to the standard output console, initializing the demo console if none was set.
+fn write_stdout(args: Arguments) -> Result {
+    STDOUT
+        .try_lock()
+        .ok_or(Error)?
+        .get_or_insert_with(Console::demo)
+        .write_fmt(args)
+}
+
+impl Write for Stdout {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result {
+        write_stdout(format_args!("{s}"))
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, args: Arguments) -> Result {
+        write_stdout(args)
+    }
+}
+
+impl Write for Stderr {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result {
+        self.write_fmt(format_args!("{s}"))
+    }
+
+    fn write_fmt(&mut self, args: Arguments) -> Result {
+        match &mut *STDERR.try_lock().ok_or(Error)? {
+            Output::Stdout => write_stdout(args),
+            Output::Console(console) => console.write_fmt(args),
+            Output::DebugPort => DebugPort.write_fmt(args),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn _print(args: Arguments) {
+    Stdout.write_fmt(args).ok();
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn _eprint(args: Arguments) {
+    Stderr.write_fmt(args).ok();
+}
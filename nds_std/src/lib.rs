@@ -9,6 +9,9 @@
 
 extern crate alloc;
 
+mod macros;
+
+pub mod io;
 pub mod ops;
 pub mod sync;
 pub mod thread;
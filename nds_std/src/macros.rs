@@ -0,0 +1,79 @@
+//! Printing macros writing to the global streams of [`crate::io`].
+
+/// Prints to the standard output.
+///
+/// Equivalent to [`println!`] without the trailing newline.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::io::_print(::core::format_args!($($arg)*))
+    };
+}
+
+/// Prints to the standard output, with a newline.
+///
+/// # Examples
+/// ```
+/// use nds::std::println;
+///
+/// println!("Hello {}!", "world");
+/// ```
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::io::_print(::core::format_args!("{}\n", ::core::format_args!($($arg)*)))
+    };
+}
+
+/// Prints to the standard error.
+///
+/// Equivalent to [`eprintln!`] without the trailing newline.
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        $crate::io::_eprint(::core::format_args!($($arg)*))
+    };
+}
+
+/// Prints to the standard error, with a newline.
+#[macro_export]
+macro_rules! eprintln {
+    () => {
+        $crate::eprint!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::io::_eprint(::core::format_args!("{}\n", ::core::format_args!($($arg)*)))
+    };
+}
+
+/// Prints and returns the value of a given expression for quick and dirty debugging.
+///
+/// The value is printed to the standard error together with its source location and expression,
+/// using its [`Debug`](core::fmt::Debug) implementation.
+#[macro_export]
+macro_rules! dbg {
+    () => {
+        $crate::eprintln!("[{}:{}:{}]", ::core::file!(), ::core::line!(), ::core::column!())
+    };
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::eprintln!(
+                    "[{}:{}:{}] {} = {:#?}",
+                    ::core::file!(),
+                    ::core::line!(),
+                    ::core::column!(),
+                    ::core::stringify!($val),
+                    &tmp,
+                );
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::dbg!($val)),+,)
+    };
+}